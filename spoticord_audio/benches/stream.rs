@@ -0,0 +1,107 @@
+//! Compares the throughput of the ring buffer with the original `Mutex<Vec<u8>>` stream.
+//!
+//! Run with `cargo bench -p spoticord_audio --bench stream`.
+
+#[allow(dead_code)]
+#[path = "../tests/reference/mod.rs"]
+mod reference;
+
+use std::{
+    hint::black_box,
+    io::{Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use reference::ReferenceStream;
+use spoticord_audio::stream::Stream;
+
+/// Librespot writes decoded audio in packets of roughly this size
+const WRITE_SIZE: usize = 4096;
+
+/// Songbird reads 20ms of 48kHz stereo audio at a time
+const READ_SIZE: usize = 3840;
+
+/// The amount of audio that is moved through every stream
+const TOTAL: usize = 64 * 1024 * 1024;
+
+/// Move audio from a writer thread to a reader thread as fast as possible
+fn transfer<S>(stream: S) -> Duration
+where
+    S: Read + Write + Clone + Send + 'static,
+{
+    let mut writer = stream.clone();
+    let mut reader = stream;
+
+    let start = Instant::now();
+
+    let handle = thread::spawn(move || {
+        let packet = [1; WRITE_SIZE];
+
+        for _ in 0..TOTAL / WRITE_SIZE {
+            writer.write_all(&packet).unwrap();
+        }
+    });
+
+    let mut received = 0;
+    let mut buf = [0; READ_SIZE];
+
+    while received < TOTAL {
+        let len = reader.read(&mut buf).unwrap();
+
+        // Silence means the writer hasn't caught up yet
+        if buf[0] != 0 {
+            received += len;
+        }
+
+        black_box(&buf);
+    }
+
+    handle.join().unwrap();
+    start.elapsed()
+}
+
+/// Run many streams at once, like a bot that is active in many servers
+fn transfer_many<S>(count: usize, create: fn() -> S) -> Duration
+where
+    S: Read + Write + Clone + Send + 'static,
+{
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..count)
+        .map(|_| {
+            let stream = create();
+            thread::spawn(move || transfer(stream))
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    start.elapsed()
+}
+
+fn report(name: &str, bytes: usize, elapsed: Duration) {
+    let throughput = bytes as f64 / elapsed.as_secs_f64() / (1024.0 * 1024.0);
+
+    println!("{name:<32} {:>10.2?} {throughput:>10.1} MiB/s", elapsed);
+}
+
+fn main() {
+    report("ring buffer", TOTAL, transfer(Stream::new()));
+    report("reference", TOTAL, transfer(ReferenceStream::new()));
+
+    let count = thread::available_parallelism().map_or(4, |count| count.get());
+
+    report(
+        &format!("ring buffer ({count} streams)"),
+        TOTAL * count,
+        transfer_many(count, Stream::new),
+    );
+    report(
+        &format!("reference ({count} streams)"),
+        TOTAL * count,
+        transfer_many(count, ReferenceStream::new),
+    );
+}
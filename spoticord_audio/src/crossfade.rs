@@ -0,0 +1,381 @@
+use crate::frame_gain;
+use librespot::playback::player::PlayerEvent;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// The longest crossfade that may be configured
+pub const MAX_CROSSFADE: Duration = Duration::from_secs(12);
+
+/// How close to the expected end of a track playback has to be to consider the track finished.
+///
+/// Track durations from Spotify's metadata don't always match the decoded audio exactly.
+const END_SLACK_MS: u32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Boundary {
+    /// The previous track ended by itself, fade its tail into the new track
+    Crossfade,
+
+    /// The user skipped, throw away whatever was held back
+    Cut,
+
+    /// The current track continues at a different position, throw away whatever was held back
+    Seek,
+}
+
+/// How far the fader is in starting a repeated track over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Restart {
+    Idle,
+
+    /// The track is about to end, which hasn't been picked up by the sink yet
+    Due,
+
+    /// The player has been asked to jump back to the start of the track
+    Requested,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    length: Duration,
+    end_of_track: bool,
+
+    /// Whether a single track is being repeated, in which case its end jumps back to its start
+    repeat: bool,
+
+    /// Events of the librespot player that haven't been picked up by the sink yet
+    events: Option<UnboundedReceiver<PlayerEvent>>,
+
+    /// Changes that happened since the sink last received audio
+    boundary: Option<Boundary>,
+    duration_ms: Option<u32>,
+    position_ms: Option<u32>,
+}
+
+impl Shared {
+    fn end_of_track(&mut self) {
+        // A repeated track continues with itself, so there's nothing to fade into
+        if !self.repeat {
+            self.end_of_track = true;
+        }
+    }
+
+    fn track_changed(&mut self, duration_ms: u32) {
+        let boundary = if std::mem::take(&mut self.end_of_track) {
+            Boundary::Crossfade
+        } else {
+            Boundary::Cut
+        };
+
+        // A jump that hasn't been processed yet must not turn into a crossfade
+        self.boundary = match self.boundary {
+            Some(Boundary::Cut | Boundary::Seek) => Some(Boundary::Cut),
+            _ => Some(boundary),
+        };
+
+        self.duration_ms = Some(duration_ms);
+        self.position_ms = Some(0);
+    }
+
+    fn seeked(&mut self, position_ms: u32) {
+        self.end_of_track = false;
+
+        // Seeking after a skip still starts a different track
+        if self.boundary != Some(Boundary::Cut) {
+            self.boundary = Some(Boundary::Seek);
+        }
+
+        self.position_ms = Some(position_ms);
+    }
+
+    /// Apply the events that the player sent before handing over the audio that is about to be processed
+    fn poll_events(&mut self) {
+        let Some(mut events) = self.events.take() else {
+            return;
+        };
+
+        while let Ok(event) = events.try_recv() {
+            match event {
+                PlayerEvent::TrackChanged { audio_item } => {
+                    self.track_changed(audio_item.duration_ms)
+                }
+                PlayerEvent::Seeked { position_ms, .. } => self.seeked(position_ms),
+                PlayerEvent::EndOfTrack { .. } => self.end_of_track(),
+                _ => {}
+            }
+        }
+
+        self.events = Some(events);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Update {
+    length: usize,
+    repeat: bool,
+    boundary: Option<Boundary>,
+    duration_ms: Option<u32>,
+    position_ms: Option<u32>,
+}
+
+/// Informs the sink about track changes, so that it can crossfade between tracks.
+///
+/// The sink holds back the last few seconds of every track to mix them into the start of the next one. It follows
+/// the events of the librespot player while it receives audio, which are sent from the same thread as the audio
+/// itself, so every track change lines up with the exact packet at which it happened.
+#[derive(Debug, Clone, Default)]
+pub struct Crossfade {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Crossfade {
+    pub fn new(length: Duration) -> Self {
+        let this = Self::default();
+        this.set_length(length);
+
+        this
+    }
+
+    /// Follow the events of the librespot player that feeds the sink
+    pub fn listen(&self, events: UnboundedReceiver<PlayerEvent>) {
+        self.lock().events = Some(events);
+    }
+
+    /// Change the length of the crossfade, a length of zero disables crossfading
+    pub fn set_length(&self, length: Duration) {
+        self.lock().length = length.min(MAX_CROSSFADE);
+    }
+
+    pub fn length(&self) -> Duration {
+        self.lock().length
+    }
+
+    /// Whether the current track starts over once it ends, instead of crossfading into the next one
+    pub fn set_repeat(&self, repeat: bool) {
+        self.lock().repeat = repeat;
+    }
+
+    /// The current track has played until the very end
+    pub fn end_of_track(&self) {
+        self.lock().end_of_track();
+    }
+
+    /// A new track has started playing.
+    ///
+    /// This crossfades if the previous track ended by itself, and cuts otherwise (e.g. when skipping).
+    pub fn track_changed(&self, duration_ms: u32) {
+        self.lock().track_changed(duration_ms);
+    }
+
+    /// The current track has been seeked to a new position
+    pub fn seeked(&self, position_ms: u32) {
+        self.lock().seeked(position_ms);
+    }
+
+    fn take(&self) -> Update {
+        let mut shared = self.lock();
+        shared.poll_events();
+
+        Update {
+            length: samples(shared.length.as_millis() as u32),
+            repeat: shared.repeat,
+            boundary: shared.boundary.take(),
+            duration_ms: shared.duration_ms.take(),
+            position_ms: shared.position_ms.take(),
+        }
+    }
+
+    /// Whether the player reported that the current track has played until the very end
+    fn ended(&self) -> bool {
+        let mut shared = self.lock();
+        shared.poll_events();
+
+        shared.end_of_track
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Shared> {
+        self.shared.lock().expect("Mutex was poisoned")
+    }
+}
+
+/// The sink side of a crossfade, which holds back and mixes the samples
+#[derive(Debug)]
+pub(crate) struct Fader {
+    crossfade: Crossfade,
+
+    /// The crossfade length in samples
+    length: usize,
+
+    /// The amount of samples of the current track that have passed through the fader
+    position: usize,
+
+    /// The expected length of the current track in samples
+    end: Option<usize>,
+
+    /// The samples at the end of the current track that are held back
+    tail: VecDeque<f32>,
+
+    /// The tail of the previous track, which is being mixed into the current track
+    fading: Option<(Vec<f32>, usize)>,
+
+    /// Whether playback jumped to a different track or position, which hasn't been picked up by the sink yet
+    cut: bool,
+
+    /// Whether the current track starts over once it ends
+    repeat: bool,
+    restart: Restart,
+}
+
+impl Fader {
+    pub(crate) fn new(crossfade: Crossfade) -> Self {
+        Self {
+            crossfade,
+            length: 0,
+            position: 0,
+            end: None,
+            tail: VecDeque::new(),
+            fading: None,
+            cut: false,
+            repeat: false,
+            restart: Restart::Idle,
+        }
+    }
+
+    /// Run samples through the crossfade, returning the samples that may be played right away
+    pub(crate) fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.apply(self.crossfade.take());
+
+        let mut output = samples.to_vec();
+        self.mix(&mut output);
+
+        let start = self.position;
+        self.position += output.len();
+
+        // The player moves on to the next track once this one ends, so it has to jump back while the track still plays
+        if self.repeat && self.restart == Restart::Idle && self.near_end() {
+            self.restart = Restart::Due;
+        }
+
+        // Once samples are being held back, everything after them has to be held back as well
+        let split = match self.end {
+            _ if !self.tail.is_empty() => 0,
+            Some(end) if self.length > 0 => end.saturating_sub(self.length).saturating_sub(start),
+            _ => output.len(),
+        };
+
+        // Never split a stereo frame in half
+        let split = usize::min(split - split % 2, output.len());
+        self.tail.extend(output.drain(split..));
+
+        // The track turned out to be longer than expected, or the crossfade got shorter
+        while self.tail.len() > self.length {
+            output.extend(self.tail.pop_front());
+        }
+
+        output
+    }
+
+    /// Whether the current track has played until its end
+    pub(crate) fn track_finished(&self) -> bool {
+        self.crossfade.ended() || self.near_end()
+    }
+
+    /// Whether playback jumped since the last call, in which case the audio before and after the jump doesn't line up
+    pub(crate) fn take_cut(&mut self) -> bool {
+        std::mem::take(&mut self.cut)
+    }
+
+    /// Whether the repeated track reached its end since the last call, in which case the player has to jump back
+    pub(crate) fn take_restart(&mut self) -> bool {
+        if self.restart != Restart::Due {
+            return false;
+        }
+
+        self.restart = Restart::Requested;
+        true
+    }
+
+    /// Whether the repeated track is waiting to start over, the audio until then is not supposed to be played
+    pub(crate) fn restarting(&self) -> bool {
+        self.restart != Restart::Idle
+    }
+
+    /// Take all samples that are being held back
+    pub(crate) fn drain(&mut self) -> Vec<f32> {
+        self.fading = None;
+        self.tail.drain(..).collect()
+    }
+
+    fn apply(&mut self, update: Update) {
+        // A repeated track starts over instead of fading into the next one, so there's no need to hold anything back
+        self.length = if update.repeat { 0 } else { update.length };
+        self.repeat = update.repeat;
+
+        match update.boundary {
+            Some(Boundary::Crossfade) => {
+                let tail = self.tail.drain(..).collect::<Vec<_>>();
+                self.fading = (!tail.is_empty()).then_some((tail, 0));
+                self.restart = Restart::Idle;
+            }
+            Some(Boundary::Seek) if self.restart == Restart::Requested => {
+                // The player jumped back to the start of the repeated track, the audio before it was never played
+                self.restart = Restart::Idle;
+            }
+            Some(Boundary::Cut | Boundary::Seek) => {
+                self.tail.clear();
+                self.fading = None;
+                self.cut = true;
+                self.restart = Restart::Idle;
+            }
+            None => {}
+        }
+
+        if !self.repeat {
+            self.restart = Restart::Idle;
+        }
+
+        if let Some(duration_ms) = update.duration_ms {
+            self.end = Some(samples(duration_ms));
+        }
+
+        if let Some(position_ms) = update.position_ms {
+            self.position = samples(position_ms);
+        }
+    }
+
+    fn near_end(&self) -> bool {
+        self.end
+            .is_some_and(|end| self.position + samples(END_SLACK_MS) >= end)
+    }
+
+    /// Fade out the tail of the previous track while fading in the current track
+    fn mix(&mut self, output: &mut [f32]) {
+        let Some((tail, index)) = &mut self.fading else {
+            return;
+        };
+
+        for sample in output.iter_mut() {
+            if *index >= tail.len() {
+                break;
+            }
+
+            let gain = frame_gain(*index, tail.len());
+            *sample = *sample * gain + tail[*index] * (1.0 - gain);
+
+            *index += 1;
+        }
+
+        if *index >= tail.len() {
+            self.fading = None;
+        }
+    }
+}
+
+/// Convert milliseconds into an amount of interleaved 44.1kHz stereo samples
+fn samples(ms: u32) -> usize {
+    ms as usize * 44100 / 1000 * 2
+}
@@ -0,0 +1,200 @@
+use std::f64::consts::{SQRT_2, TAU};
+
+use crate::filter::AudioFilter;
+
+/// The amount of bands of the equalizer
+pub const BANDS: usize = 5;
+
+/// The center frequency (in Hz) of every band, the lowest and highest band are shelves
+pub const BAND_FREQUENCIES: [f64; BANDS] = [60.0, 250.0, 1000.0, 4000.0, 12000.0];
+
+/// The most a band may be boosted or cut (in dB)
+pub const MAX_GAIN_DB: f32 = 12.0;
+
+/// How wide the bands in between the shelves are, roughly two octaves
+const BAND_Q: f64 = 0.7;
+
+const SAMPLE_RATE: f64 = 44100.0;
+
+/// The gain (in dB) of every band of the equalizer
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Preset {
+    #[default]
+    Flat,
+    BassBoost,
+    Vocal,
+    Treble,
+    Loudness,
+    Custom([f32; BANDS]),
+}
+
+impl Preset {
+    pub fn gains(&self) -> [f32; BANDS] {
+        match self {
+            Self::Flat => [0.0; BANDS],
+            Self::BassBoost => [6.0, 3.0, 0.0, 0.0, 0.0],
+            Self::Vocal => [-2.0, -1.0, 3.0, 3.0, 0.0],
+            Self::Treble => [0.0, 0.0, 0.0, 3.0, 6.0],
+            Self::Loudness => [5.0, 1.0, 0.0, 1.0, 4.0],
+            Self::Custom(gains) => gains.map(|gain| gain.clamp(-MAX_GAIN_DB, MAX_GAIN_DB)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Flat => "Flat",
+            Self::BassBoost => "Bass boost",
+            Self::Vocal => "Vocal",
+            Self::Treble => "Treble",
+            Self::Loudness => "Loudness",
+            Self::Custom(_) => "Custom",
+        }
+    }
+
+    /// Whether this preset leaves the audio untouched
+    pub fn is_flat(&self) -> bool {
+        self.gains().iter().all(|&gain| gain == 0.0)
+    }
+}
+
+/// A multi-band equalizer, made up of a low shelf, a few peaking filters and a high shelf
+#[derive(Debug)]
+pub struct Equalizer {
+    bands: Vec<Biquad>,
+
+    /// Lowers the volume by part of the largest boost, to leave some headroom for the boosted frequencies
+    preamp: f32,
+}
+
+impl Equalizer {
+    pub fn new(preset: Preset) -> Self {
+        let gains = preset.gains();
+        let last = BANDS - 1;
+
+        let bands = gains
+            .iter()
+            .zip(BAND_FREQUENCIES)
+            .enumerate()
+            .filter(|(_, (&gain, _))| gain != 0.0)
+            .map(|(band, (&gain, frequency))| match band {
+                0 => Biquad::low_shelf(frequency, gain as f64),
+                band if band == last => Biquad::high_shelf(frequency, gain as f64),
+                _ => Biquad::peaking(frequency, gain as f64, BAND_Q),
+            })
+            .collect();
+
+        let boost = gains.iter().copied().fold(0.0, f32::max);
+
+        Self {
+            bands,
+            preamp: 10f32.powf(-boost / 2.0 / 20.0),
+        }
+    }
+}
+
+impl AudioFilter for Equalizer {
+    fn process(&mut self, samples: &mut [f32]) {
+        if self.bands.is_empty() {
+            return;
+        }
+
+        for frame in samples.chunks_exact_mut(2) {
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let mut value = *sample as f64;
+
+                for band in &mut self.bands {
+                    value = band.process(channel, value);
+                }
+
+                *sample = (value as f32 * self.preamp).clamp(-1.0, 1.0);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        for band in &mut self.bands {
+            band.state = [[0.0; 2]; 2];
+        }
+    }
+}
+
+/// A second order filter, using the formulas from the Audio EQ Cookbook by Robert Bristow-Johnson
+#[derive(Debug)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+
+    /// The state of every channel
+    state: [[f64; 2]; 2],
+}
+
+impl Biquad {
+    fn peaking(frequency: f64, gain_db: f64, q: f64) -> Self {
+        let a = 10f64.powf(gain_db / 40.0);
+        let (sin, cos) = (TAU * frequency / SAMPLE_RATE).sin_cos();
+        let alpha = sin / (2.0 * q);
+
+        Self::new(
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        )
+    }
+
+    fn low_shelf(frequency: f64, gain_db: f64) -> Self {
+        let a = 10f64.powf(gain_db / 40.0);
+        let (sin, cos) = (TAU * frequency / SAMPLE_RATE).sin_cos();
+        let beta = a.sqrt() * sin * SQRT_2;
+
+        Self::new(
+            [
+                a * ((a + 1.0) - (a - 1.0) * cos + beta),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - beta),
+            ],
+            [
+                (a + 1.0) + (a - 1.0) * cos + beta,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - beta,
+            ],
+        )
+    }
+
+    fn high_shelf(frequency: f64, gain_db: f64) -> Self {
+        let a = 10f64.powf(gain_db / 40.0);
+        let (sin, cos) = (TAU * frequency / SAMPLE_RATE).sin_cos();
+        let beta = a.sqrt() * sin * SQRT_2;
+
+        Self::new(
+            [
+                a * ((a + 1.0) + (a - 1.0) * cos + beta),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - beta),
+            ],
+            [
+                (a + 1.0) - (a - 1.0) * cos + beta,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - beta,
+            ],
+        )
+    }
+
+    /// Normalise the coefficients so that `a0` is one
+    fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b: b.map(|b| b / a[0]),
+            a: [a[1] / a[0], a[2] / a[0]],
+            state: [[0.0; 2]; 2],
+        }
+    }
+
+    /// Process a single sample of a channel, in transposed direct form II
+    fn process(&mut self, channel: usize, input: f64) -> f64 {
+        let state = &mut self.state[channel];
+
+        let output = self.b[0] * input + state[0];
+        state[0] = self.b[1] * input - self.a[0] * output + state[1];
+        state[1] = self.b[2] * input - self.a[1] * output;
+
+        output
+    }
+}
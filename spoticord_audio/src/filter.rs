@@ -0,0 +1,156 @@
+use crate::frame_gain;
+use std::sync::{Arc, Mutex};
+
+/// How long (in interleaved 44.1kHz stereo samples) the old and new chain are blended when swapping, 10ms
+const SWAP_LENGTH: usize = 882;
+
+/// A processing step that is applied to audio before it is sent to Discord
+pub trait AudioFilter: Send {
+    /// Process a block of interleaved 44.1kHz stereo samples in place
+    fn process(&mut self, samples: &mut [f32]);
+
+    /// Forget about previous audio, as what follows does not continue from it (e.g. after a skip)
+    fn reset(&mut self) {}
+}
+
+/// An ordered list of filters, where every filter processes the output of the one before it
+#[derive(Default)]
+pub struct FilterChain {
+    filters: Vec<Box<dyn AudioFilter>>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a filter to the end of the chain
+    pub fn with(mut self, filter: impl AudioFilter + 'static) -> Self {
+        self.push(filter);
+        self
+    }
+
+    pub fn push(&mut self, filter: impl AudioFilter + 'static) {
+        self.filters.push(Box::new(filter));
+    }
+
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+}
+
+impl AudioFilter for FilterChain {
+    fn process(&mut self, samples: &mut [f32]) {
+        for filter in &mut self.filters {
+            filter.process(samples);
+        }
+    }
+
+    fn reset(&mut self) {
+        for filter in &mut self.filters {
+            filter.reset();
+        }
+    }
+}
+
+impl std::fmt::Debug for FilterChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterChain")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Hands new filter chains to the sink while audio is playing.
+///
+/// The sink picks up a new chain before processing its next packet, and blends the output of the old
+/// and new chain for a few milliseconds so that the change doesn't cause a gap or click.
+#[derive(Debug, Clone, Default)]
+pub struct Filters {
+    pending: Arc<Mutex<Option<FilterChain>>>,
+}
+
+impl Filters {
+    pub fn new(chain: FilterChain) -> Self {
+        let this = Self::default();
+        this.set(chain);
+
+        this
+    }
+
+    /// Replace the filter chain, an empty chain passes audio through untouched
+    pub fn set(&self, chain: FilterChain) {
+        *self.pending.lock().expect("Mutex was poisoned") = Some(chain);
+    }
+
+    fn take(&self) -> Option<FilterChain> {
+        self.pending.lock().expect("Mutex was poisoned").take()
+    }
+}
+
+/// The sink side of the filters, which runs the samples through the active chain
+#[derive(Debug)]
+pub(crate) struct FilterStage {
+    filters: Filters,
+    chain: FilterChain,
+
+    /// The chain that was replaced, along with how far the blend into the new chain has progressed
+    previous: Option<(FilterChain, usize)>,
+}
+
+impl FilterStage {
+    pub(crate) fn new(filters: Filters) -> Self {
+        // The initial chain applies right away, as there is no audio yet to blend from
+        let chain = filters.take().unwrap_or_default();
+
+        Self {
+            filters,
+            chain,
+            previous: None,
+        }
+    }
+
+    pub(crate) fn process(&mut self, samples: &mut [f32]) {
+        if let Some(chain) = self.filters.take() {
+            let previous = std::mem::replace(&mut self.chain, chain);
+
+            // Swapping between two empty chains has nothing to blend
+            self.previous =
+                (!previous.is_empty() || !self.chain.is_empty()).then_some((previous, 0));
+        }
+
+        let Some((previous, index)) = &mut self.previous else {
+            self.chain.process(samples);
+            return;
+        };
+
+        let mut old = samples.to_vec();
+        previous.process(&mut old);
+        self.chain.process(samples);
+
+        for (sample, old) in samples.iter_mut().zip(old) {
+            if *index >= SWAP_LENGTH {
+                break;
+            }
+
+            let gain = frame_gain(*index, SWAP_LENGTH);
+            *sample = *sample * gain + old * (1.0 - gain);
+
+            *index += 1;
+        }
+
+        if *index >= SWAP_LENGTH {
+            self.previous = None;
+        }
+    }
+
+    /// Forget about previous audio, used when playback is interrupted
+    pub(crate) fn reset(&mut self) {
+        self.previous = None;
+        self.chain.reset();
+    }
+}
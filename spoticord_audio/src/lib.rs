@@ -1,2 +1,13 @@
+pub mod crossfade;
+pub mod equalizer;
+pub mod filter;
 pub mod sink;
+pub mod speed;
 pub mod stream;
+
+/// The gain at `index` of a ramp from silence to full volume that spans `length` interleaved stereo samples.
+///
+/// Both channels of a frame get the same gain, so the ramp never shifts the stereo image.
+pub(crate) fn frame_gain(index: usize, length: usize) -> f32 {
+    (index - index % 2) as f32 / length as f32
+}
@@ -1,3 +1,7 @@
+use crate::crossfade::{Crossfade, Fader};
+use crate::filter::{FilterStage, Filters};
+use crate::frame_gain;
+use crate::speed::{Speed, SpeedStage};
 use crate::stream::Stream;
 use librespot::playback::audio_backend::{Sink, SinkAsBytes, SinkError, SinkResult};
 use librespot::playback::convert::Converter;
@@ -8,21 +12,98 @@ use tokio::sync::mpsc::UnboundedSender;
 pub enum SinkEvent {
     Start,
     Stop,
+
+    /// The track that is being repeated is about to end, the player has to jump back to its start before Spotify
+    /// moves on to the next track
+    RepeatTrack,
 }
 
 pub struct StreamSink {
     stream: Stream,
     sender: UnboundedSender<SinkEvent>,
+    fader: Fader,
+    filters: FilterStage,
+    speed: SpeedStage,
+
+    /// How far audio has faded in after playback started or jumped, `None` once it is at full volume
+    fade_in: Option<usize>,
+
+    /// How far audio has faded out while waiting for a repeated track to start over
+    fade_out: Option<usize>,
 }
 
 impl StreamSink {
-    pub fn new(stream: Stream, sender: UnboundedSender<SinkEvent>) -> Self {
-        Self { stream, sender }
+    pub fn new(
+        stream: Stream,
+        sender: UnboundedSender<SinkEvent>,
+        crossfade: Crossfade,
+        filters: Filters,
+        speed: Speed,
+    ) -> Self {
+        Self {
+            stream,
+            sender,
+            fader: Fader::new(crossfade),
+            filters: FilterStage::new(filters),
+            speed: SpeedStage::new(speed),
+            fade_in: None,
+            fade_out: None,
+        }
+    }
+
+    /// Ramp up the volume of the first samples after playback started or jumped, so that they don't start with a click
+    fn fade_in(&mut self, samples: &mut [f32]) {
+        let Some(index) = &mut self.fade_in else {
+            return;
+        };
+
+        let length = self.stream.fade_samples();
+
+        for sample in samples.iter_mut() {
+            if *index >= length {
+                break;
+            }
+
+            *sample *= frame_gain(*index, length);
+
+            *index += 1;
+        }
+
+        if *index >= length {
+            self.fade_in = None;
+        }
+    }
+
+    /// Ramp down the volume of the last samples before a repeated track starts over, dropping everything after them
+    fn fade_out(&mut self, samples: &mut Vec<f32>) {
+        let Some(index) = &mut self.fade_out else {
+            return;
+        };
+
+        let length = self.stream.fade_samples();
+        let mut kept = 0;
+
+        for sample in samples.iter_mut() {
+            if *index >= length {
+                break;
+            }
+
+            // The last frame is silent, so that the start of the track fades in from silence
+            *sample *= 1.0 - frame_gain(*index + 2, length);
+
+            *index += 1;
+            kept += 1;
+        }
+
+        samples.truncate(kept);
     }
 }
 
 impl Sink for StreamSink {
     fn start(&mut self) -> SinkResult<()> {
+        self.stream.set_active(true);
+        self.fade_in = Some(0);
+
         if let Err(_why) = self.sender.send(SinkEvent::Start) {
             // WARNING: Returning an error causes librespot-playback to panic
 
@@ -33,13 +114,29 @@ impl Sink for StreamSink {
     }
 
     fn stop(&mut self) -> SinkResult<()> {
+        // Running out of audio after this point is expected, and should not count as an underrun
+        self.stream.set_active(false);
+
         if let Err(_why) = self.sender.send(SinkEvent::Stop) {
             // WARNING: Returning an error causes librespot-playback to panic
 
             // return Err(SinkError::ConnectionRefused(_why.to_string()));
         }
 
-        self.stream.flush().ok();
+        if self.fader.track_finished() {
+            use zerocopy::IntoBytes;
+
+            // Playback has ended, so the end of the track that was held back for crossfading can be played
+            let mut tail = self.speed.process(&self.fader.drain());
+            tail.extend(self.speed.drain());
+
+            self.write_bytes(tail.as_bytes())?;
+        } else {
+            // The stream fades out whatever it was playing before skipping the rest
+            self.stream.flush().ok();
+            self.filters.reset();
+            self.speed.reset();
+        }
 
         Ok(())
     }
@@ -51,7 +148,33 @@ impl Sink for StreamSink {
             return Ok(());
         };
 
-        self.write_bytes(converter.f64_to_f32(&samples).as_bytes())?;
+        let mut samples = converter.f64_to_f32(&samples);
+        self.filters.process(&mut samples);
+
+        let samples = self.fader.process(&samples);
+
+        if self.fader.take_cut() {
+            // Audio from before a skip or seek would otherwise run straight into the audio after it
+            self.stream.flush().ok();
+            self.speed.reset();
+            self.fade_in = Some(0);
+            self.fade_out = None;
+        }
+
+        if self.fader.take_restart() {
+            _ = self.sender.send(SinkEvent::RepeatTrack);
+            self.fade_out = Some(0);
+        } else if !self.fader.restarting() && self.fade_out.take().is_some() {
+            // The audio before the jump has faded out, so the start of the track can follow it right away
+            self.speed.reset();
+            self.fade_in = Some(0);
+        }
+
+        let mut samples = self.speed.process(&samples);
+        self.fade_out(&mut samples);
+        self.fade_in(&mut samples);
+
+        self.write_bytes(samples.as_bytes())?;
 
         Ok(())
     }
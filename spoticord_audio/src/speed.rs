@@ -0,0 +1,325 @@
+use std::{
+    f32::consts::TAU,
+    sync::{Arc, Mutex},
+};
+
+/// The slowest speed at which audio can be played
+pub const MIN_SPEED: f32 = 0.5;
+
+/// The fastest speed at which audio can be played
+pub const MAX_SPEED: f32 = 2.0;
+
+/// How long (in 44.1kHz frames) a segment of the time-stretcher is, 40ms
+const SEGMENT: usize = 1764;
+
+/// How far apart (in frames) the segments end up in the output, which makes them overlap by half
+const HOP: usize = SEGMENT / 2;
+
+/// How far (in frames) a segment may be moved to line up with the audio before it, 5ms
+const TOLERANCE: usize = 220;
+
+/// How a different playback speed is achieved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpeedMode {
+    /// Play the samples faster or slower, which also raises or lowers the pitch
+    #[default]
+    Resample,
+
+    /// Stretch the audio in time while keeping the pitch intact, which suits speech
+    Stretch,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    factor: f32,
+    mode: SpeedMode,
+}
+
+/// Controls how fast the sink plays audio, changes apply from the next packet onwards
+#[derive(Debug, Clone)]
+pub struct Speed {
+    settings: Arc<Mutex<Settings>>,
+}
+
+impl Speed {
+    pub fn new(factor: f32) -> Self {
+        Self {
+            settings: Arc::new(Mutex::new(Settings {
+                factor: factor.clamp(MIN_SPEED, MAX_SPEED),
+                mode: SpeedMode::default(),
+            })),
+        }
+    }
+
+    /// The speed relative to normal playback, e.g. `1.25` plays a minute of audio in 48 seconds
+    pub fn factor(&self) -> f32 {
+        self.settings().factor
+    }
+
+    pub fn set_factor(&self, factor: f32) {
+        self.lock().factor = factor.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    pub fn mode(&self) -> SpeedMode {
+        self.settings().mode
+    }
+
+    pub fn set_mode(&self, mode: SpeedMode) {
+        self.lock().mode = mode;
+    }
+
+    fn settings(&self) -> Settings {
+        *self.lock()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Settings> {
+        self.settings.lock().expect("Mutex was poisoned")
+    }
+}
+
+impl Default for Speed {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+/// The sink side of the speed control, which changes the amount of samples that are played
+#[derive(Debug)]
+pub(crate) struct SpeedStage {
+    speed: Speed,
+
+    /// The mode that is processing audio, `None` while playing at normal speed
+    active: Option<SpeedMode>,
+
+    resampler: Resampler,
+    stretcher: Stretcher,
+}
+
+impl SpeedStage {
+    pub(crate) fn new(speed: Speed) -> Self {
+        Self {
+            speed,
+            active: None,
+            resampler: Resampler::default(),
+            stretcher: Stretcher::default(),
+        }
+    }
+
+    pub(crate) fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let Settings { factor, mode } = self.speed.settings();
+        let active = (factor != 1.0).then_some(mode);
+
+        let mut input = Vec::new();
+
+        if active != self.active {
+            // The audio that the previous mode still held on to continues seamlessly into the next mode
+            input = self.drain();
+            self.active = active;
+        }
+
+        let samples = if input.is_empty() {
+            samples
+        } else {
+            input.extend_from_slice(samples);
+            &input
+        };
+
+        let factor = factor as f64;
+
+        match self.active {
+            None => samples.to_vec(),
+            Some(SpeedMode::Resample) => self.resampler.process(factor, samples),
+            Some(SpeedMode::Stretch) => self.stretcher.process(factor, samples),
+        }
+    }
+
+    /// Hand out the audio that is still held back, without changing its speed
+    pub(crate) fn drain(&mut self) -> Vec<f32> {
+        match self.active {
+            None => vec![],
+            Some(SpeedMode::Resample) => self.resampler.drain(),
+            Some(SpeedMode::Stretch) => self.stretcher.drain(),
+        }
+    }
+
+    /// Forget about previous audio, used when playback is interrupted
+    pub(crate) fn reset(&mut self) {
+        self.drain();
+    }
+}
+
+/// Changes the speed by reading through the audio at a different rate, interpolating between frames
+#[derive(Debug, Default)]
+struct Resampler {
+    input: Vec<[f32; 2]>,
+
+    /// Where the next output frame is read from, relative to the start of `input`
+    position: f64,
+}
+
+impl Resampler {
+    fn process(&mut self, factor: f64, samples: &[f32]) -> Vec<f32> {
+        self.input.extend(frames(samples));
+
+        let mut output = Vec::with_capacity((samples.len() as f64 / factor) as usize + 2);
+
+        while self.position + 1.0 < self.input.len() as f64 {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            let (current, next) = (self.input[index], self.input[index + 1]);
+
+            for channel in 0..2 {
+                output.push(current[channel] + (next[channel] - current[channel]) * fraction);
+            }
+
+            self.position += factor;
+        }
+
+        let consumed = (self.position as usize).min(self.input.len());
+        self.input.drain(..consumed);
+        self.position -= consumed as f64;
+
+        output
+    }
+
+    fn drain(&mut self) -> Vec<f32> {
+        let output = self.input.drain(..).flatten().collect();
+        self.position = 0.0;
+
+        output
+    }
+}
+
+/// Changes the speed without affecting the pitch, using waveform similarity overlap-add (WSOLA).
+///
+/// The audio is cut into overlapping segments, which are placed closer together or further apart. Every
+///  segment is shifted slightly to line up with the one before it, which avoids phasing artifacts.
+#[derive(Debug, Default)]
+struct Stretcher {
+    input: Vec<[f32; 2]>,
+
+    /// Where the next segment would ideally start, relative to the start of `input`
+    position: f64,
+
+    /// Where the audio that naturally follows the previous segment starts, relative to the start of `input`
+    continuation: usize,
+
+    /// The second half of the previous segment, already faded out
+    overlap: Vec<[f32; 2]>,
+}
+
+impl Stretcher {
+    fn process(&mut self, factor: f64, samples: &[f32]) -> Vec<f32> {
+        self.input.extend(frames(samples));
+
+        let mut output = vec![];
+
+        if self.overlap.is_empty() {
+            if self.input.len() < HOP {
+                return output;
+            }
+
+            // Pretend that a segment ended right before the start, so the first one lines up with the audio itself
+            self.overlap = (0..HOP)
+                .map(|index| scale(self.input[index], window(HOP + index)))
+                .collect();
+            self.continuation = 0;
+            self.position = 0.0;
+        }
+
+        loop {
+            let ideal = self.position.round() as usize;
+
+            // Every candidate segment has to be available in full
+            if ideal + TOLERANCE + SEGMENT > self.input.len() {
+                break;
+            }
+
+            let start = self.best_match(ideal.saturating_sub(TOLERANCE), ideal + TOLERANCE);
+
+            for index in 0..HOP {
+                let frame = scale(self.input[start + index], window(index));
+                output.extend([
+                    self.overlap[index][0] + frame[0],
+                    self.overlap[index][1] + frame[1],
+                ]);
+
+                self.overlap[index] = scale(self.input[start + HOP + index], window(HOP + index));
+            }
+
+            self.continuation = start + HOP;
+            self.position += HOP as f64 * factor;
+
+            // Forget the audio that can no longer end up in a segment
+            let unused = self
+                .continuation
+                .min((self.position as usize).saturating_sub(TOLERANCE));
+
+            self.input.drain(..unused);
+            self.continuation -= unused;
+            self.position -= unused as f64;
+        }
+
+        output
+    }
+
+    /// Find the segment within the given range that looks most like the audio following the previous segment
+    fn best_match(&self, low: usize, high: usize) -> usize {
+        // Only every 4th frame is compared, which is plenty to line up the waveforms
+        let reference: Vec<f32> = self.input[self.continuation..self.continuation + HOP]
+            .iter()
+            .step_by(4)
+            .map(|frame| frame[0] + frame[1])
+            .collect();
+
+        let mut best = (low, f32::MIN);
+
+        for start in (low..=high).step_by(2) {
+            let (correlation, energy) = self.input[start..start + HOP]
+                .iter()
+                .step_by(4)
+                .map(|frame| frame[0] + frame[1])
+                .zip(&reference)
+                .fold((0.0, 0.0), |(correlation, energy), (value, reference)| {
+                    (correlation + value * reference, energy + value * value)
+                });
+
+            let similarity = correlation / (energy + 1e-9).sqrt();
+
+            if similarity > best.1 {
+                best = (start, similarity);
+            }
+        }
+
+        best.0
+    }
+
+    fn drain(&mut self) -> Vec<f32> {
+        // The faded out half of the previous segment would add up to the audio that follows it, so play that as is
+        let output = if self.overlap.is_empty() {
+            self.input.drain(..).flatten().collect()
+        } else {
+            self.input.drain(self.continuation..).flatten().collect()
+        };
+
+        self.input.clear();
+        self.overlap.clear();
+        self.continuation = 0;
+        self.position = 0.0;
+
+        output
+    }
+}
+
+fn frames(samples: &[f32]) -> impl Iterator<Item = [f32; 2]> + '_ {
+    samples.chunks_exact(2).map(|frame| [frame[0], frame[1]])
+}
+
+fn scale(frame: [f32; 2], gain: f32) -> [f32; 2] {
+    [frame[0] * gain, frame[1] * gain]
+}
+
+/// A Hann window over a segment, two of these that overlap by half always add up to one
+fn window(index: usize) -> f32 {
+    0.5 - 0.5 * (TAU * index as f32 / SEGMENT as f32).cos()
+}
@@ -1,45 +1,483 @@
 use std::{
+    cell::UnsafeCell,
     io::{Read, Seek, Write},
-    sync::{Arc, Condvar, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use songbird::input::core::io::MediaSource;
 
-/// The lower the value, the less latency
+/// The amount of audio the buffer starts out holding, before it has adapted to the connection.
 ///
-/// Too low of a value results in jittery audio
-const BUFFER_SIZE: usize = 64 * 1024;
+/// The lower the value, the less latency, too low of a value results in jittery audio.
+const INITIAL_TARGET: usize = 64 * 1024;
 
-#[derive(Clone, Default)]
+/// The smallest buffer that is allowed, regardless of the configured bounds
+const MIN_BUFFER_SIZE: usize = 8 * 1024;
+
+/// How long playback has to go without underruns before the buffer shrinks
+const SHRINK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Marks that there is no flush waiting to be picked up by the reader
+const NO_FLUSH: usize = usize::MAX;
+
+/// The size (in bytes) of a single frame of 44.1kHz stereo `f32` audio
+const FRAME: usize = 8;
+
+/// How large (in bytes) the buffer of a [`Stream`] is allowed to become
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferBounds {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Default for BufferBounds {
+    fn default() -> Self {
+        Self {
+            min: 32 * 1024,
+            max: 256 * 1024,
+        }
+    }
+}
+
+/// A snapshot of how well the audio has been flowing through a [`Stream`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamStats {
+    /// The amount of times the reader ran out of audio during playback
+    pub underruns: u64,
+
+    /// The amount of bytes of silence that were handed out because no audio was available
+    pub silence_bytes: u64,
+
+    /// The most bytes that have been waiting to be read at once
+    pub peak_fill: usize,
+
+    /// The amount of bytes the buffer currently holds before writes block
+    pub target: usize,
+}
+
+impl std::fmt::Display for StreamStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} underruns, {} bytes of silence, peak fill of {}/{} bytes",
+            self.underruns, self.silence_bytes, self.peak_fill, self.target
+        )
+    }
+}
+
+/// A ring buffer that carries audio from librespot to songbird.
+///
+/// There must only be a single writer (the librespot player thread) and a single reader (the songbird mixer).
+/// Neither of them takes a lock, unless the writer has to wait for the reader to make room.
+///
+/// Flushed audio can be faded out instead of cut off, in which case the reader first plays a short ramp down to
+/// silence from wherever it was, and only then skips over the rest.
+///
+/// The buffer starts out small to keep latency low. Every underrun during playback grows it by half, up to the
+/// maximum bound, while every minute of playback without underruns shrinks it by an eighth, down to the minimum bound.
+#[derive(Clone)]
 pub struct Stream {
-    inner: Arc<(Mutex<Vec<u8>>, Condvar)>,
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// Sized to the maximum bound, of which only `target` bytes are filled
+    buffer: Box<[UnsafeCell<u8>]>,
+    bounds: BufferBounds,
+    target: AtomicUsize,
+
+    /// The total amount of bytes that have been read and written, these only ever grow
+    read: AtomicUsize,
+    write: AtomicUsize,
+
+    /// The write position at the time of a flush, the reader skips ahead to it on its next read
+    flush: AtomicUsize,
+
+    /// How many bytes of flushed audio are faded out before skipping over the rest
+    fade: AtomicUsize,
+
+    /// Where the fade out of a flush ends, and where the reader continues after that
+    fade_end: AtomicUsize,
+    skip: AtomicUsize,
+
+    /// Used by the writer to sleep while the buffer is full
+    waiting: AtomicBool,
+    lock: Mutex<()>,
+    space: Condvar,
+
+    /// Set once the stream is no longer played, after which writes are discarded instead of blocking
+    closed: AtomicBool,
+
+    created_at: Instant,
+
+    /// When the reader ran out of audio (in nanoseconds since `created_at`, plus one), or zero if it has audio
+    starved_since: AtomicU64,
+
+    /// Whether the writer is playing, outside of that running out of audio is expected
+    active: AtomicBool,
+
+    /// Whether the reader has received audio since the writer started playing
+    primed: AtomicBool,
+
+    /// When the target was last changed (in nanoseconds since `created_at`)
+    adjusted_at: AtomicU64,
+
+    underruns: AtomicU64,
+    silence_bytes: AtomicU64,
+    peak_fill: AtomicUsize,
 }
 
+// SAFETY: The writer only touches the bytes between `write` and `read + buffer.len()`, while the reader only
+// touches (and fades out) the bytes between `read` and `write`. Both positions are published with release/acquire
+// ordering, so the two sides never access the same bytes at the same time.
+unsafe impl Sync for Inner {}
+
 impl Stream {
     pub fn new() -> Self {
-        Self::default()
+        Self::with_bounds(BufferBounds::default())
+    }
+
+    pub fn with_bounds(bounds: BufferBounds) -> Self {
+        let min = usize::max(bounds.min, MIN_BUFFER_SIZE);
+        let bounds = BufferBounds {
+            min,
+            max: usize::max(bounds.max, min),
+        };
+
+        Self {
+            inner: Arc::new(Inner {
+                buffer: (0..bounds.max).map(|_| UnsafeCell::new(0)).collect(),
+                bounds,
+                target: AtomicUsize::new(INITIAL_TARGET.clamp(bounds.min, bounds.max)),
+                read: AtomicUsize::new(0),
+                write: AtomicUsize::new(0),
+                flush: AtomicUsize::new(NO_FLUSH),
+                fade: AtomicUsize::new(0),
+                fade_end: AtomicUsize::new(0),
+                skip: AtomicUsize::new(0),
+                waiting: AtomicBool::new(false),
+                lock: Mutex::new(()),
+                space: Condvar::new(),
+                closed: AtomicBool::new(false),
+                created_at: Instant::now(),
+                starved_since: AtomicU64::new(0),
+                active: AtomicBool::new(false),
+                primed: AtomicBool::new(false),
+                adjusted_at: AtomicU64::new(0),
+                underruns: AtomicU64::new(0),
+                silence_bytes: AtomicU64::new(0),
+                peak_fill: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// The amount of bytes that can be waiting to be read before writes block
+    pub fn capacity(&self) -> usize {
+        self.inner.target.load(Ordering::Relaxed)
+    }
+
+    /// The amount of bytes that are waiting to be read
+    pub fn len(&self) -> usize {
+        let flush = self.inner.flush.load(Ordering::Acquire);
+        let read = self.inner.read.load(Ordering::Acquire);
+        let write = self.inner.write.load(Ordering::Acquire);
+
+        // Flushed audio is already gone as far as everyone else is concerned, even while it fades out
+        let read = match flush {
+            NO_FLUSH => read,
+            flush => usize::max(read, flush),
+        };
+        let read = usize::max(read, self.inner.skip.load(Ordering::Acquire));
+
+        write.saturating_sub(read)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How long the reader has been receiving silence because no audio was available
+    pub fn starved_for(&self) -> Option<Duration> {
+        match self.inner.starved_since.load(Ordering::Relaxed) {
+            0 => None,
+            since => Some(
+                self.inner
+                    .created_at
+                    .elapsed()
+                    .saturating_sub(Duration::from_nanos(since - 1)),
+            ),
+        }
+    }
+
+    /// How long flushed audio fades out before it is skipped, zero cuts it off right away
+    pub fn set_fade(&self, length: Duration) {
+        let frames = length.as_millis() as usize * 44100 / 1000;
+
+        self.inner.fade.store(frames * FRAME, Ordering::Relaxed);
+    }
+
+    /// The amount of interleaved samples over which audio fades in or out
+    pub fn fade_samples(&self) -> usize {
+        self.inner.fade.load(Ordering::Relaxed) / FRAME * 2
+    }
+
+    /// Mark whether audio is being played, so that gaps in between playback aren't counted as underruns
+    pub fn set_active(&self, active: bool) {
+        if active {
+            self.inner.primed.store(false, Ordering::Relaxed);
+        }
+
+        self.inner.active.store(active, Ordering::Relaxed);
+    }
+
+    /// Stop accepting audio, which wakes up a writer that is waiting for room that will never be made.
+    ///
+    /// Everything that is written afterwards is thrown away, the reader still plays what was already buffered.
+    pub fn close(&self) {
+        self.inner.closed.store(true, Ordering::SeqCst);
+
+        let _guard = self.inner.lock.lock().expect("Mutex was poisoned");
+        self.inner.space.notify_all();
+    }
+
+    pub fn stats(&self) -> StreamStats {
+        StreamStats {
+            underruns: self.inner.underruns.load(Ordering::Relaxed),
+            silence_bytes: self.inner.silence_bytes.load(Ordering::Relaxed),
+            peak_fill: self.inner.peak_fill.load(Ordering::Relaxed),
+            target: self.capacity(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Stream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stream")
+            .field("len", &self.len())
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl Default for Stream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inner {
+    /// Copy bytes into the buffer, starting at the given position.
+    ///
+    /// # Safety
+    ///
+    /// Only the writer may call this for bytes that the reader has already consumed, and only the reader may call
+    /// this for bytes that the writer has published but haven't been read yet.
+    unsafe fn copy_in(&self, position: usize, data: &[u8]) {
+        let start = position % self.buffer.len();
+        let first = usize::min(data.len(), self.buffer.len() - start);
+        let base = UnsafeCell::raw_get(self.buffer.as_ptr());
+
+        std::ptr::copy_nonoverlapping(data.as_ptr(), base.add(start), first);
+        std::ptr::copy_nonoverlapping(data.as_ptr().add(first), base, data.len() - first);
+    }
+
+    /// Copy bytes out of the buffer, starting at the given position.
+    ///
+    /// # Safety
+    ///
+    /// Only the reader may call this, and only for bytes that the writer has already published.
+    unsafe fn copy_out(&self, position: usize, data: &mut [u8]) {
+        let start = position % self.buffer.len();
+        let first = usize::min(data.len(), self.buffer.len() - start);
+        let base = UnsafeCell::raw_get(self.buffer.as_ptr());
+
+        std::ptr::copy_nonoverlapping(base.add(start), data.as_mut_ptr(), first);
+        std::ptr::copy_nonoverlapping(base, data.as_mut_ptr().add(first), data.len() - first);
+    }
+
+    /// Lower the volume of the audio after `read` down to silence, returning where the fade out ends.
+    ///
+    /// # Safety
+    ///
+    /// Only the reader may call this, and `flush` may not be past the bytes that the writer has published.
+    unsafe fn fade_out(&self, read: usize, flush: usize) -> usize {
+        // Partially read frames play as they are, so that the fade starts at a whole sample
+        let start = read.next_multiple_of(FRAME);
+        let end = usize::min(start + self.fade.load(Ordering::Relaxed), flush);
+        let end = end - end % FRAME;
+
+        if end <= start {
+            return read;
+        }
+
+        let mut audio = vec![0u8; end - start];
+        self.copy_out(start, &mut audio);
+
+        let frames = audio.len() / FRAME;
+
+        for (index, frame) in audio.chunks_exact_mut(FRAME).enumerate() {
+            // The last frame is silent, so that whatever plays next starts from silence
+            let gain = 1.0 - (index + 1) as f32 / frames as f32;
+
+            for sample in frame.chunks_exact_mut(4) {
+                let value =
+                    f32::from_ne_bytes(sample.try_into().expect("sample is 4 bytes")) * gain;
+                sample.copy_from_slice(&value.to_ne_bytes());
+            }
+        }
+
+        self.copy_in(start, &audio);
+
+        end
+    }
+
+    /// Sleep until the reader has made room in the buffer
+    fn wait_for_space(&self, write: usize) {
+        let guard = self.lock.lock().expect("Mutex was poisoned");
+        self.waiting.store(true, Ordering::SeqCst);
+
+        // The reader might have made room (or the stream was closed) before it could see that we're waiting
+        if self.closed.load(Ordering::SeqCst)
+            || write - self.read.load(Ordering::SeqCst) < self.target.load(Ordering::SeqCst)
+        {
+            self.waiting.store(false, Ordering::SeqCst);
+            return;
+        }
+
+        let _guard = self.space.wait(guard).expect("Mutex was poisoned");
+        self.waiting.store(false, Ordering::SeqCst);
+    }
+
+    /// Wake up the writer if it is waiting for room in the buffer
+    fn notify_space(&self) {
+        if self.waiting.load(Ordering::SeqCst) {
+            let _guard = self.lock.lock().expect("Mutex was poisoned");
+            self.space.notify_one();
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.created_at.elapsed().as_nanos() as u64
+    }
+
+    /// Mark the reader as out of audio, returning whether it had audio before
+    fn starve(&self) -> bool {
+        self.starved_since
+            .compare_exchange(0, self.now() + 1, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Whether the reader is expected to receive audio
+    fn playing(&self) -> bool {
+        self.active.load(Ordering::Relaxed) && self.primed.load(Ordering::Relaxed)
+    }
+
+    /// Grow the buffer after an underrun, so that the writer can get further ahead of the reader
+    fn grow(&self) {
+        let target = self.target.load(Ordering::Relaxed);
+
+        self.target.store(
+            usize::min(target + target / 2, self.bounds.max),
+            Ordering::SeqCst,
+        );
+        self.adjusted_at.store(self.now(), Ordering::Relaxed);
+
+        self.notify_space();
+    }
+
+    /// Shrink the buffer if playback has been going smoothly for a while, which lowers the latency
+    fn shrink_if_stable(&self) {
+        let now = self.now();
+        let adjusted_at = self.adjusted_at.load(Ordering::Relaxed);
+
+        if Duration::from_nanos(now - adjusted_at) < SHRINK_INTERVAL {
+            return;
+        }
+
+        let target = self.target.load(Ordering::Relaxed);
+
+        self.target.store(
+            usize::max(target - target / 8, self.bounds.min),
+            Ordering::SeqCst,
+        );
+        self.adjusted_at.store(now, Ordering::Relaxed);
     }
 }
 
 impl Read for Stream {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let (mutex, condvar) = &*self.inner;
-        let mut buffer = mutex.lock().expect("Mutex was poisoned");
+        let inner = &*self.inner;
+        let mut read = inner.read.load(Ordering::Relaxed);
+
+        // Fade out what was about to play after a flush, a fade that is already playing is kept as is
+        let flush = inner.flush.swap(NO_FLUSH, Ordering::Acquire);
+        if flush != NO_FLUSH && flush > read {
+            if read >= inner.fade_end.load(Ordering::Relaxed) {
+                // SAFETY: The bytes up to `flush` have been published by the writer
+                let end = unsafe { inner.fade_out(read, flush) };
+                inner.fade_end.store(end, Ordering::Relaxed);
+            }
+
+            inner.skip.store(flush, Ordering::Release);
+        }
+
+        let fade_end = inner.fade_end.load(Ordering::Relaxed);
+
+        // Skip over everything that was written before the last flush, once it has faded out
+        let skip = inner.skip.load(Ordering::Relaxed);
+        if read >= fade_end && skip > read {
+            read = skip;
+            inner.read.store(read, Ordering::SeqCst);
+            inner.notify_space();
+        }
+
+        let available = if read < fade_end {
+            fade_end - read
+        } else {
+            inner.write.load(Ordering::Acquire) - read
+        };
 
         // Prevent Discord jitter by filling buffer with zeroes if we don't have any audio
         // (i.e. when you skip too far ahead in a song which hasn't been downloaded yet)
-        if buffer.is_empty() {
+        if available == 0 {
             buf.fill(0);
-            condvar.notify_all();
+
+            let underrun = inner.starve();
+
+            if inner.playing() {
+                inner
+                    .silence_bytes
+                    .fetch_add(buf.len() as u64, Ordering::Relaxed);
+
+                if underrun {
+                    inner.underruns.fetch_add(1, Ordering::Relaxed);
+                    inner.grow();
+                }
+            }
 
             return Ok(buf.len());
         }
 
-        let max_read = usize::min(buf.len(), buffer.len());
+        inner.starved_since.store(0, Ordering::Relaxed);
 
-        buf[0..max_read].copy_from_slice(&buffer[0..max_read]);
-        buffer.drain(0..max_read);
-        condvar.notify_all();
+        if inner.playing() {
+            inner.shrink_if_stable();
+        } else if inner.active.load(Ordering::Relaxed) {
+            inner.primed.store(true, Ordering::Relaxed);
+            inner.adjusted_at.store(inner.now(), Ordering::Relaxed);
+        }
+
+        let max_read = usize::min(buf.len(), available);
+
+        // SAFETY: The bytes up to `write` have been published by the writer
+        unsafe { inner.copy_out(read, &mut buf[..max_read]) };
+
+        inner.read.store(read + max_read, Ordering::SeqCst);
+        inner.notify_space();
 
         Ok(max_read)
     }
@@ -47,25 +485,50 @@ impl Read for Stream {
 
 impl Write for Stream {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        let (mutex, condvar) = &*self.inner;
-        let mut buffer = mutex.lock().expect("Mutex was poisoned");
-
-        while buffer.len() + buf.len() > BUFFER_SIZE {
-            buffer = condvar.wait(buffer).expect("Mutex was poisoned");
+        if buf.is_empty() {
+            return Ok(0);
         }
 
-        buffer.extend_from_slice(buf);
-        condvar.notify_all();
+        let inner = &*self.inner;
+        let write = inner.write.load(Ordering::Relaxed);
 
-        Ok(buf.len())
+        let (used, free) = loop {
+            // Nobody is going to read this anymore, pretend it was written so the writer can wind down
+            if inner.closed.load(Ordering::SeqCst) {
+                return Ok(buf.len());
+            }
+
+            let used = write - inner.read.load(Ordering::Acquire);
+            let target = inner.target.load(Ordering::Acquire);
+
+            if used < target {
+                break (used, target - used);
+            }
+
+            inner.wait_for_space(write);
+        };
+
+        let max_write = usize::min(buf.len(), free);
+
+        // SAFETY: The bytes after `write` have been consumed by the reader
+        unsafe { inner.copy_in(write, &buf[..max_write]) };
+
+        inner.write.store(write + max_write, Ordering::Release);
+        inner
+            .peak_fill
+            .fetch_max(used + max_write, Ordering::Relaxed);
+
+        Ok(max_write)
     }
 
+    /// Throw away all audio that hasn't been read yet.
+    ///
+    /// The reader skips over the flushed audio on its next read, or after fading it out if a fade is set. Until then it
+    /// still takes up room in the buffer, use [`Stream::close`] to release a writer when nothing reads anymore.
     fn flush(&mut self) -> std::io::Result<()> {
-        let (mutex, condvar) = &*self.inner;
-        let mut buffer = mutex.lock().expect("Mutex was poisoned");
-
-        buffer.clear();
-        condvar.notify_all();
+        // This may be called from outside of the writer thread, so make sure to see its latest position
+        let write = self.inner.write.load(Ordering::Acquire);
+        self.inner.flush.store(write, Ordering::Release);
 
         Ok(())
     }
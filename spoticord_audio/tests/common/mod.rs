@@ -0,0 +1,149 @@
+//! A sink that writes into a stream, along with the helpers to feed it audio and read back what comes out
+
+// Not every test uses every helper
+#![allow(dead_code)]
+
+use std::{f64::consts::TAU, io::Read, time::Duration};
+
+use librespot::playback::{audio_backend::Sink, convert::Converter, decoder::AudioPacket};
+use spoticord_audio::{
+    crossfade::Crossfade,
+    filter::{FilterChain, Filters},
+    sink::{SinkEvent, StreamSink},
+    speed::Speed,
+    stream::Stream,
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// 10ms of 44.1kHz stereo audio
+pub const PACKET: usize = 882;
+
+/// Interleaved stereo samples of a 440Hz sine wave, starting at the given frame
+pub fn sine(start: usize, samples: usize) -> Vec<f64> {
+    (start..start + samples / 2)
+        .flat_map(|frame| {
+            let value = (TAU * 440.0 * frame as f64 / 44100.0).sin() * 0.5;
+            [value, value]
+        })
+        .collect()
+}
+
+pub struct Harness {
+    pub sink: StreamSink,
+    pub stream: Stream,
+    pub converter: Converter,
+    pub crossfade: Crossfade,
+    pub filters: Filters,
+    pub speed: Speed,
+    pub events: UnboundedReceiver<SinkEvent>,
+
+    /// The amount of frames of the sine wave that have been played
+    pub frames: usize,
+    pub output: Vec<f32>,
+}
+
+impl Harness {
+    /// A sink without crossfading, filters or speed changes, which can be enabled through the controllers
+    pub fn new() -> Self {
+        Self::with_filters(FilterChain::new())
+    }
+
+    /// The filter chain that the sink starts out with applies right away, unlike chains that are set later on
+    pub fn with_filters(chain: FilterChain) -> Self {
+        let stream = Stream::new();
+        let crossfade = Crossfade::new(Duration::ZERO);
+        let filters = Filters::new(chain);
+        let speed = Speed::default();
+        let (tx, events) = tokio::sync::mpsc::unbounded_channel();
+
+        Self {
+            sink: StreamSink::new(
+                stream.clone(),
+                tx,
+                crossfade.clone(),
+                filters.clone(),
+                speed.clone(),
+            ),
+            stream,
+            converter: Converter::new(None),
+            crossfade,
+            filters,
+            speed,
+            events,
+            frames: 0,
+            output: vec![],
+        }
+    }
+
+    /// Write `packets` packets that consist of a single constant value, without reading them
+    pub fn write(&mut self, value: f64, packets: usize) {
+        for _ in 0..packets {
+            self.sink
+                .write(
+                    AudioPacket::Samples(vec![value; PACKET]),
+                    &mut self.converter,
+                )
+                .expect("write failed");
+        }
+    }
+
+    /// Play a track of `duration_ms` that consists of a single constant value
+    pub fn play(&mut self, value: f64, duration_ms: u32) {
+        for _ in 0..duration_ms / 10 {
+            self.write(value, 1);
+            self.read();
+        }
+    }
+
+    /// Play `packets` packets of a continuous sine wave
+    pub fn play_sine(&mut self, packets: usize) {
+        for _ in 0..packets {
+            let samples = sine(self.frames, PACKET);
+            self.frames += PACKET / 2;
+
+            self.sink
+                .write(AudioPacket::Samples(samples), &mut self.converter)
+                .expect("write failed");
+
+            self.read();
+        }
+    }
+
+    /// The sine wave that went into the sink, as the sink would have converted it
+    pub fn input(&self) -> Vec<f32> {
+        sine(0, self.frames * 2)
+            .into_iter()
+            .map(|sample| sample as f32)
+            .collect()
+    }
+
+    /// Read everything that made it into the stream, so that writes never block
+    pub fn read(&mut self) {
+        let mut buf = vec![0u8; self.stream.len()];
+        self.stream.read_exact(&mut buf).expect("read failed");
+
+        self.collect(&buf);
+    }
+
+    /// Read `packets` packets worth of audio, the way songbird would
+    pub fn read_packets(&mut self, packets: usize) {
+        for _ in 0..packets {
+            let mut buf = vec![0u8; PACKET * 4];
+            let mut filled = 0;
+
+            // A fade out ends in the middle of a read, after which the reader continues with the next audio
+            while filled < buf.len() {
+                filled += self.stream.read(&mut buf[filled..]).expect("read failed");
+            }
+
+            self.collect(&buf);
+        }
+    }
+
+    fn collect(&mut self, buf: &[u8]) {
+        self.output.extend(
+            buf.chunks_exact(4)
+                .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap())),
+        );
+    }
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{Harness, PACKET};
+use librespot::{
+    core::SpotifyId,
+    playback::{audio_backend::Sink, player::PlayerEvent},
+};
+use spoticord_audio::sink::SinkEvent;
+
+/// The amount of interleaved samples in 100ms of audio
+const CROSSFADE: usize = PACKET * 10;
+
+fn crossfading(length: Duration) -> Harness {
+    let harness = Harness::new();
+    harness.crossfade.set_length(length);
+
+    harness
+}
+
+#[test]
+fn crossfade_mixes_tail_into_next_track() {
+    let mut harness = crossfading(Duration::from_millis(100));
+
+    harness.crossfade.track_changed(300);
+    harness.play(1.0, 300);
+
+    // The last 100ms are held back
+    assert_eq!(harness.output.len(), PACKET * 20);
+    assert!(harness.output.iter().all(|&sample| sample == 1.0));
+
+    harness.crossfade.end_of_track();
+    harness.crossfade.track_changed(300);
+    harness.play(-1.0, 300);
+
+    let output = &harness.output;
+    assert_eq!(output.len(), PACKET * 20 + PACKET * 30 - CROSSFADE);
+
+    let mix = &output[PACKET * 20..PACKET * 20 + CROSSFADE];
+
+    // The mix starts at the outgoing track and moves towards the incoming track
+    assert_eq!(mix[0], 1.0);
+    assert!(mix[CROSSFADE - 1] < -0.99);
+    assert!(mix.windows(2).all(|pair| pair[1] <= pair[0]));
+
+    // Both channels of a frame are mixed the same way
+    assert!(mix.chunks_exact(2).all(|frame| frame[0] == frame[1]));
+
+    // Halfway through, both tracks are equally loud
+    assert!(mix[CROSSFADE / 2].abs() < 0.01);
+
+    assert!(output[PACKET * 20 + CROSSFADE..]
+        .iter()
+        .all(|&sample| sample == -1.0));
+}
+
+#[test]
+fn skip_cuts_without_crossfade() {
+    let mut harness = crossfading(Duration::from_millis(100));
+
+    harness.crossfade.track_changed(300);
+    harness.play(1.0, 300);
+
+    // No end of track, so the user skipped
+    harness.crossfade.track_changed(300);
+    harness.play(-1.0, 300);
+
+    let output = &harness.output;
+    assert_eq!(output.len(), PACKET * 20 + PACKET * 20);
+    assert!(output[..PACKET * 20].iter().all(|&sample| sample == 1.0));
+    assert!(output[PACKET * 20..].iter().all(|&sample| sample == -1.0));
+}
+
+#[test]
+fn seek_discards_held_tail() {
+    let mut harness = crossfading(Duration::from_millis(100));
+
+    harness.crossfade.track_changed(300);
+    harness.play(1.0, 300);
+
+    harness.crossfade.seeked(0);
+    harness.play(0.5, 100);
+
+    let output = &harness.output;
+    assert_eq!(output.len(), PACKET * 20 + PACKET * 10);
+    assert!(output[..PACKET * 20].iter().all(|&sample| sample == 1.0));
+    assert!(output[PACKET * 20..].iter().all(|&sample| sample == 0.5));
+}
+
+#[test]
+fn disabled_crossfade_passes_audio_through() {
+    let mut harness = crossfading(Duration::ZERO);
+
+    harness.crossfade.track_changed(300);
+    harness.play(1.0, 300);
+
+    assert_eq!(harness.output.len(), PACKET * 30);
+
+    harness.crossfade.end_of_track();
+    harness.crossfade.track_changed(300);
+    harness.play(-1.0, 300);
+
+    let output = &harness.output;
+    assert_eq!(output.len(), PACKET * 60);
+    assert!(output[..PACKET * 30].iter().all(|&sample| sample == 1.0));
+    assert!(output[PACKET * 30..].iter().all(|&sample| sample == -1.0));
+}
+
+#[test]
+fn track_longer_than_expected_is_not_held_back_forever() {
+    let mut harness = crossfading(Duration::from_millis(100));
+
+    // Metadata claims 200ms, but the track is 400ms long
+    harness.crossfade.track_changed(200);
+    harness.play(1.0, 400);
+
+    assert_eq!(harness.output.len(), PACKET * 40 - CROSSFADE);
+}
+
+#[test]
+fn stopping_at_end_of_playback_plays_tail() {
+    let mut harness = crossfading(Duration::from_millis(100));
+
+    harness.crossfade.track_changed(300);
+    harness.play(1.0, 300);
+    harness.crossfade.end_of_track();
+
+    harness.sink.stop().expect("stop failed");
+    harness.read();
+
+    assert_eq!(harness.output.len(), PACKET * 30);
+    assert!(harness.output.iter().all(|&sample| sample == 1.0));
+}
+
+#[test]
+fn player_events_apply_to_the_audio_that_follows() {
+    let mut harness = crossfading(Duration::from_millis(100));
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    harness.crossfade.listen(rx);
+
+    harness.crossfade.track_changed(300);
+    harness.play(1.0, 300);
+
+    // The player seeks in between two packets, so everything that is written afterwards belongs to the new position
+    let track_id = SpotifyId::from_uri("spotify:track:4uLU6hMCjMI75M1A2tKUQC").unwrap();
+    tx.send(PlayerEvent::Seeked {
+        play_request_id: 0,
+        track_id,
+        position_ms: 0,
+    })
+    .unwrap();
+
+    harness.play(0.5, 100);
+
+    let output = &harness.output;
+    assert_eq!(output.len(), PACKET * 20 + PACKET * 10);
+    assert!(output[PACKET * 20..].iter().all(|&sample| sample == 0.5));
+}
+
+#[test]
+fn repeated_track_starts_over_before_it_ends() {
+    let mut harness = crossfading(Duration::from_millis(100));
+    harness.crossfade.set_repeat(true);
+
+    harness.crossfade.track_changed(1000);
+    harness.play(1.0, 400);
+
+    // Nothing is held back, as the track doesn't fade into another one
+    assert_eq!(harness.output.len(), PACKET * 40);
+    assert!(harness.events.try_recv().is_err());
+
+    // Spotify would move on to the next track once it ends, so the player is asked to jump back before that
+    harness.play(1.0, 200);
+    assert!(matches!(
+        harness.events.try_recv(),
+        Ok(SinkEvent::RepeatTrack)
+    ));
+
+    // The audio after the request is dropped, as the player is about to jump back anyway
+    harness.crossfade.seeked(0);
+    harness.play(-1.0, 100);
+
+    let output = &harness.output;
+    assert_eq!(output.len(), PACKET * 49 + PACKET * 10);
+    assert!(output[..PACKET * 49].iter().all(|&sample| sample == 1.0));
+    assert!(output[PACKET * 49..].iter().all(|&sample| sample == -1.0));
+}
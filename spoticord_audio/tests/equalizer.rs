@@ -0,0 +1,97 @@
+use std::f64::consts::TAU;
+
+use spoticord_audio::{
+    equalizer::{Equalizer, Preset, BANDS, MAX_GAIN_DB},
+    filter::AudioFilter,
+};
+
+/// One second of an interleaved stereo sine wave
+fn sine(frequency: f64) -> Vec<f32> {
+    (0..44100)
+        .flat_map(|frame| {
+            let value = ((TAU * frequency * frame as f64 / 44100.0).sin() * 0.25) as f32;
+            [value, value]
+        })
+        .collect()
+}
+
+/// How much louder (in dB) a sine wave comes out of the equalizer, ignoring the time it takes to settle
+fn response(preset: Preset, frequency: f64) -> f64 {
+    let input = sine(frequency);
+    let mut output = input.clone();
+
+    Equalizer::new(preset).process(&mut output);
+
+    let rms = |samples: &[f32]| {
+        let settled = &samples[samples.len() / 2..];
+        (settled.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / settled.len() as f64).sqrt()
+    };
+
+    20.0 * (rms(&output) / rms(&input)).log10()
+}
+
+#[test]
+fn flat_leaves_audio_untouched() {
+    let input = sine(440.0);
+    let mut output = input.clone();
+
+    Equalizer::new(Preset::Flat).process(&mut output);
+
+    assert_eq!(input, output);
+}
+
+#[test]
+fn bass_boost_raises_low_frequencies() {
+    let low = response(Preset::BassBoost, 50.0);
+    let high = response(Preset::BassBoost, 5000.0);
+
+    assert!(low - high > 4.0, "low {low:.1}dB, high {high:.1}dB");
+}
+
+#[test]
+fn treble_raises_high_frequencies() {
+    let low = response(Preset::Treble, 100.0);
+    let high = response(Preset::Treble, 14000.0);
+
+    assert!(high - low > 4.0, "low {low:.1}dB, high {high:.1}dB");
+}
+
+#[test]
+fn vocal_raises_speech_frequencies() {
+    let low = response(Preset::Vocal, 60.0);
+    let voice = response(Preset::Vocal, 2000.0);
+
+    assert!(voice - low > 3.0, "low {low:.1}dB, voice {voice:.1}dB");
+}
+
+#[test]
+fn custom_band_gains_are_applied() {
+    let mut gains = [0.0; BANDS];
+    gains[2] = -MAX_GAIN_DB;
+
+    let mid = response(Preset::Custom(gains), 1000.0);
+    let low = response(Preset::Custom(gains), 60.0);
+
+    assert!((mid + MAX_GAIN_DB as f64).abs() < 1.0, "mid {mid:.1}dB");
+    assert!(low.abs() < 1.0, "low {low:.1}dB");
+}
+
+#[test]
+fn custom_gains_are_limited() {
+    let gains = Preset::Custom([100.0, -100.0, 0.0, 0.0, 0.0]).gains();
+
+    assert_eq!(gains[0], MAX_GAIN_DB);
+    assert_eq!(gains[1], -MAX_GAIN_DB);
+}
+
+#[test]
+fn output_never_clips() {
+    let mut samples = sine(60.0)
+        .into_iter()
+        .map(|sample| sample * 4.0)
+        .collect::<Vec<_>>();
+
+    Equalizer::new(Preset::Custom([MAX_GAIN_DB; BANDS])).process(&mut samples);
+
+    assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+}
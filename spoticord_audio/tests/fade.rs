@@ -0,0 +1,117 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{Harness, PACKET};
+use librespot::playback::audio_backend::Sink;
+use spoticord_audio::crossfade::Crossfade;
+
+/// The amount of interleaved samples in a 20ms fade
+const FADE: usize = PACKET * 2;
+
+/// The largest jump between two samples that is allowed, a click would be orders of magnitude larger
+const MAX_STEP: f32 = 0.005;
+
+/// A harness that is playing, and fades for `length`
+fn fading(length: Duration) -> Harness {
+    let mut harness = Harness::new();
+    harness.stream.set_fade(length);
+    harness.sink.start().expect("start failed");
+
+    harness
+}
+
+fn max_step(output: &[f32]) -> f32 {
+    output
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).abs())
+        .fold(0.0, f32::max)
+}
+
+#[test]
+fn stopping_fades_out() {
+    let mut harness = fading(Duration::from_millis(20));
+
+    harness.write(0.5, 10);
+    harness.read_packets(3);
+
+    harness.sink.stop().expect("stop failed");
+    harness.read_packets(10);
+
+    let step = max_step(&harness.output);
+    assert!(step < MAX_STEP, "{step}");
+
+    // Only the fade was played, the rest of the buffered audio was skipped
+    let after = &harness.output[PACKET * 3..];
+    let audible = after.iter().filter(|sample| **sample != 0.0).count();
+    assert!(audible <= FADE, "{audible} samples played after stopping");
+    assert!(after[FADE..].iter().all(|sample| *sample == 0.0));
+}
+
+#[test]
+fn starting_fades_in() {
+    let mut harness = fading(Duration::from_millis(20));
+
+    harness.write(0.5, 10);
+    harness.read_packets(3);
+
+    harness.sink.stop().expect("stop failed");
+    harness.read_packets(5);
+
+    harness.sink.start().expect("start failed");
+    harness.write(0.5, 10);
+    harness.read_packets(10);
+
+    let step = max_step(&harness.output);
+    assert!(step < MAX_STEP, "{step}");
+
+    // Playback is back at full volume once the fade has passed
+    assert_eq!(harness.output.last(), Some(&0.5));
+}
+
+#[test]
+fn jumping_fades_between_audio() {
+    let jumps: [fn(&Crossfade); 2] = [
+        |crossfade| crossfade.track_changed(60_000),
+        |crossfade| crossfade.seeked(30_000),
+    ];
+
+    for jump in jumps {
+        let mut harness = fading(Duration::from_millis(20));
+        harness.crossfade.track_changed(60_000);
+
+        harness.write(0.5, 10);
+        harness.read_packets(3);
+
+        jump(&harness.crossfade);
+        harness.write(-0.5, 10);
+        harness.read_packets(10);
+
+        let step = max_step(&harness.output);
+        assert!(step < MAX_STEP, "{step}");
+
+        // The old audio faded out to silence, and the new audio faded in from it
+        let jumped = &harness.output[PACKET * 3..];
+        let silent = jumped.iter().position(|sample| *sample == 0.0);
+        let after = &jumped[silent.expect("audio never reached silence")..];
+        assert!(after.iter().all(|sample| *sample <= 0.0));
+        assert_eq!(after.last(), Some(&-0.5));
+    }
+}
+
+#[test]
+fn fading_can_be_disabled() {
+    let mut harness = fading(Duration::ZERO);
+
+    harness.write(0.5, 10);
+    harness.read_packets(3);
+
+    harness.sink.stop().expect("stop failed");
+    harness.read_packets(2);
+
+    // The buffered audio is cut off right away
+    assert_eq!(harness.output[PACKET * 3 - 1], 0.5);
+    assert!(harness.output[PACKET * 3..]
+        .iter()
+        .all(|sample| *sample == 0.0));
+}
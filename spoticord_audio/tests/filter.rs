@@ -0,0 +1,108 @@
+mod common;
+
+use std::f64::consts::TAU;
+
+use common::{Harness, PACKET};
+use spoticord_audio::filter::{AudioFilter, FilterChain};
+
+/// Multiplies every sample by a constant
+struct Gain(f32);
+
+impl AudioFilter for Gain {
+    fn process(&mut self, samples: &mut [f32]) {
+        samples.iter_mut().for_each(|sample| *sample *= self.0);
+    }
+}
+
+/// Adds a constant to every sample
+struct Offset(f32);
+
+impl AudioFilter for Offset {
+    fn process(&mut self, samples: &mut [f32]) {
+        samples.iter_mut().for_each(|sample| *sample += self.0);
+    }
+}
+
+fn assert_close(actual: &[f32], expected: impl IntoIterator<Item = f32>) {
+    let expected = expected.into_iter().collect::<Vec<_>>();
+    assert_eq!(actual.len(), expected.len());
+
+    for (index, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "sample {index}: expected {expected}, got {actual}"
+        );
+    }
+}
+
+#[test]
+fn empty_chain_passes_audio_through() {
+    let mut harness = Harness::with_filters(FilterChain::new());
+    harness.play_sine(10);
+
+    assert_close(&harness.output, harness.input());
+}
+
+#[test]
+fn filters_run_in_order() {
+    let mut harness = Harness::with_filters(FilterChain::new().with(Gain(2.0)).with(Offset(0.25)));
+    harness.play_sine(10);
+
+    assert_close(
+        &harness.output,
+        harness.input().iter().map(|sample| sample * 2.0 + 0.25),
+    );
+
+    let mut harness = Harness::with_filters(FilterChain::new().with(Offset(0.25)).with(Gain(2.0)));
+    harness.play_sine(10);
+
+    assert_close(
+        &harness.output,
+        harness.input().iter().map(|sample| (sample + 0.25) * 2.0),
+    );
+}
+
+#[test]
+fn swapping_filters_does_not_interrupt_audio() {
+    let mut harness = Harness::with_filters(FilterChain::new());
+    harness.play_sine(10);
+
+    harness.filters.set(FilterChain::new().with(Gain(0.0)));
+    harness.play_sine(10);
+
+    let input = harness.input();
+    let output = &harness.output;
+
+    // Not a single sample went missing
+    assert_eq!(output.len(), input.len());
+    assert_close(&output[..PACKET * 10], input[..PACKET * 10].iter().copied());
+
+    // The old filters fade into the new ones over the course of a single packet
+    let blend = &output[PACKET * 10..PACKET * 11];
+    assert!(blend
+        .iter()
+        .zip(&input[PACKET * 10..PACKET * 11])
+        .all(|(output, input)| output.abs() <= input.abs()));
+    assert!(output[PACKET * 11..].iter().all(|&sample| sample == 0.0));
+
+    // No jumps that are larger than the sine wave and the blend would make together
+    let max_step = (TAU * 440.0 / 44100.0 * 0.5 + 0.5 / (PACKET / 2) as f64) as f32 * 1.01;
+    assert!(output
+        .chunks_exact(2)
+        .zip(output.chunks_exact(2).skip(1))
+        .all(|(a, b)| (a[0] - b[0]).abs() <= max_step));
+}
+
+#[test]
+fn both_channels_are_filtered_the_same() {
+    let mut harness = Harness::with_filters(FilterChain::new().with(Gain(0.5)));
+    harness.play_sine(5);
+
+    harness.filters.set(FilterChain::new().with(Gain(1.5)));
+    harness.play_sine(5);
+
+    assert!(harness
+        .output
+        .chunks_exact(2)
+        .all(|frame| frame[0] == frame[1]));
+}
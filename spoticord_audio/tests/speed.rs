@@ -0,0 +1,143 @@
+mod common;
+
+use std::f64::consts::TAU;
+
+use common::Harness;
+use librespot::playback::audio_backend::Sink;
+use spoticord_audio::speed::{Speed, SpeedMode};
+
+fn playing_at(factor: f32, mode: SpeedMode) -> Harness {
+    let harness = Harness::new();
+    harness.speed.set_factor(factor);
+    harness.speed.set_mode(mode);
+
+    harness
+}
+
+/// Let the track end, which plays whatever the sink was still holding on to
+fn finish(harness: &mut Harness) {
+    harness.crossfade.end_of_track();
+    harness.sink.stop().expect("stop failed");
+    harness.read();
+}
+
+/// The pitch (in Hz) of the left channel of the output, skipping the first and last 100ms
+fn frequency(output: &[f32]) -> f64 {
+    let left: Vec<f32> = output.iter().step_by(2).copied().collect();
+    let left = &left[4410..left.len() - 4410];
+
+    let crossings = left
+        .windows(2)
+        .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+        .count();
+
+    crossings as f64 * 44100.0 / left.len() as f64
+}
+
+/// The largest difference between two consecutive samples of the left channel
+fn max_step(output: &[f32]) -> f32 {
+    output
+        .chunks_exact(2)
+        .zip(output.chunks_exact(2).skip(1))
+        .map(|(a, b)| (a[0] - b[0]).abs())
+        .fold(0.0, f32::max)
+}
+
+/// How large a step between two samples of the sine wave can be at the given pitch
+fn sine_step(frequency: f64) -> f32 {
+    (TAU * frequency / 44100.0 * 0.5) as f32
+}
+
+#[test]
+fn normal_speed_passes_audio_through() {
+    let mut harness = playing_at(1.0, SpeedMode::Stretch);
+    harness.play_sine(10);
+
+    assert_eq!(harness.output, harness.input());
+}
+
+#[test]
+fn resampling_changes_duration_and_pitch() {
+    for factor in [0.8, 1.25, 1.5] {
+        let mut harness = playing_at(factor, SpeedMode::Resample);
+        harness.play_sine(200);
+        finish(&mut harness);
+
+        let expected = harness.frames as f64 * 2.0 / factor as f64;
+        let actual = harness.output.len() as f64;
+        assert!(
+            (actual - expected).abs() < 4.0,
+            "{factor}x: expected {expected} samples, got {actual}"
+        );
+
+        let frequency = frequency(&harness.output);
+        assert!(
+            (frequency - 440.0 * factor as f64).abs() < 440.0 * factor as f64 * 0.02,
+            "{factor}x: expected a pitch of {}Hz, got {frequency}Hz",
+            440.0 * factor
+        );
+    }
+}
+
+#[test]
+fn stretching_changes_duration_but_keeps_pitch() {
+    for factor in [0.75, 1.25, 2.0] {
+        let mut harness = playing_at(factor, SpeedMode::Stretch);
+        harness.play_sine(200);
+        finish(&mut harness);
+
+        // Segments are placed 20ms apart, so the length can be off by part of a segment
+        let expected = harness.frames as f64 * 2.0 / factor as f64;
+        let actual = harness.output.len() as f64;
+        assert!(
+            (actual - expected).abs() < 1764.0 * 2.0,
+            "{factor}x: expected {expected} samples, got {actual}"
+        );
+
+        let frequency = frequency(&harness.output);
+        assert!(
+            (frequency - 440.0).abs() < 440.0 * 0.02,
+            "{factor}x: expected a pitch of 440Hz, got {frequency}Hz"
+        );
+
+        // Segments are lined up with each other, so there are no clicks where they overlap
+        assert!(max_step(&harness.output) <= sine_step(440.0) * 1.1);
+    }
+}
+
+#[test]
+fn changing_speed_does_not_interrupt_audio() {
+    for mode in [SpeedMode::Resample, SpeedMode::Stretch] {
+        let mut harness = playing_at(1.0, mode);
+        harness.play_sine(50);
+
+        harness.speed.set_factor(1.5);
+        harness.play_sine(50);
+
+        harness.speed.set_factor(0.75);
+        harness.play_sine(50);
+
+        harness.speed.set_factor(1.0);
+        harness.play_sine(50);
+
+        // The highest pitch is reached while resampling at 1.5x
+        let limit = match mode {
+            SpeedMode::Resample => sine_step(440.0 * 1.5),
+            SpeedMode::Stretch => sine_step(440.0),
+        };
+
+        assert!(
+            max_step(&harness.output) <= limit * 1.1,
+            "{mode:?}: audio jumps when the speed changes"
+        );
+    }
+}
+
+#[test]
+fn speed_is_limited() {
+    let speed = Speed::new(10.0);
+    assert_eq!(speed.factor(), 2.0);
+
+    speed.set_factor(0.1);
+    assert_eq!(speed.factor(), 0.5);
+}
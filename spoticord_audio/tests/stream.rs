@@ -0,0 +1,254 @@
+mod reference;
+
+use std::{
+    io::{Read, Write},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use reference::ReferenceStream;
+use spoticord_audio::stream::{BufferBounds, Stream};
+
+/// A tiny deterministic random number generator, so that failures can be reproduced
+struct Random(u64);
+
+impl Random {
+    /// A number between 1 and `max` (inclusive)
+    fn next(&mut self, max: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        (self.0 >> 33) as usize % max + 1
+    }
+}
+
+/// Audio that never contains a zero byte, so that it can be told apart from the silence of an empty stream
+fn audio(offset: usize, len: usize) -> Vec<u8> {
+    (offset..offset + len)
+        .map(|i| (i % 255) as u8 + 1)
+        .collect()
+}
+
+#[test]
+fn matches_reference_implementation() {
+    let mut stream = Stream::new();
+    let mut reference = ReferenceStream::new();
+    let mut random = Random(0x5eed);
+    let mut written = 0;
+
+    for _ in 0..20_000 {
+        let action = random.next(20);
+
+        // Flushed audio is only skipped over by the next read, which songbird would do soon after
+        if action == 1 {
+            stream.flush().unwrap();
+            reference.flush().unwrap();
+        }
+
+        match action {
+            2..=10 => {
+                let len = random.next(8192);
+
+                // Only write what fits, as a single thread can't wait for itself to make room
+                if stream.len() + len <= stream.capacity() {
+                    let data = audio(written, len);
+                    written += len;
+
+                    stream.write_all(&data).unwrap();
+                    reference.write_all(&data).unwrap();
+                }
+            }
+            _ => {
+                let len = random.next(8192);
+                let mut buf = vec![0xff; len];
+                let mut expected = vec![0xff; len];
+
+                assert_eq!(
+                    stream.read(&mut buf).unwrap(),
+                    reference.read(&mut expected).unwrap()
+                );
+                assert_eq!(buf, expected);
+            }
+        }
+
+        assert_eq!(stream.len(), reference.len());
+    }
+}
+
+#[test]
+fn empty_stream_reads_silence() {
+    let mut stream = Stream::new();
+    let mut buf = [0xff; 1024];
+
+    assert_eq!(stream.read(&mut buf).unwrap(), buf.len());
+    assert!(buf.iter().all(|&byte| byte == 0));
+    assert!(stream.starved_for().is_some());
+
+    stream.write_all(&audio(0, 16)).unwrap();
+
+    assert_eq!(stream.read(&mut buf).unwrap(), 16);
+    assert_eq!(buf[..16], audio(0, 16));
+    assert!(stream.starved_for().is_none());
+}
+
+#[test]
+fn flush_discards_unread_audio() {
+    let mut stream = Stream::new();
+
+    stream.write_all(&audio(0, 1000)).unwrap();
+    stream.flush().unwrap();
+
+    assert!(stream.is_empty());
+
+    stream.write_all(&[7; 10]).unwrap();
+    assert_eq!(stream.len(), 10);
+
+    let mut buf = [0; 100];
+    assert_eq!(stream.read(&mut buf).unwrap(), 10);
+    assert!(buf[..10].iter().all(|&byte| byte == 7));
+}
+
+#[test]
+fn writes_block_until_there_is_room() {
+    let mut stream = Stream::new();
+    stream.write_all(&audio(0, stream.capacity())).unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    let mut writer = stream.clone();
+
+    thread::spawn(move || {
+        writer.write_all(&[1]).unwrap();
+        tx.send(()).unwrap();
+    });
+
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+    let mut buf = [0; 1];
+    stream.read_exact(&mut buf).unwrap();
+
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn closing_releases_a_blocked_writer() {
+    let stream = Stream::new();
+    let mut writer = stream.clone();
+    writer.write_all(&audio(0, stream.capacity())).unwrap();
+
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        writer.flush().unwrap();
+        writer.write_all(&audio(0, 1000)).unwrap();
+        tx.send(()).unwrap();
+    });
+
+    // Flushing alone doesn't make room until the reader skips over the flushed audio
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+    stream.close();
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn underruns_grow_the_buffer() {
+    let mut stream = Stream::with_bounds(BufferBounds {
+        min: 32 * 1024,
+        max: 128 * 1024,
+    });
+    let initial = stream.capacity();
+    let mut buf = [0; 4000];
+
+    stream.set_active(true);
+    stream.write_all(&audio(0, 1000)).unwrap();
+
+    assert_eq!(stream.read(&mut buf).unwrap(), 1000);
+
+    // Running dry is a single underrun, no matter how long it takes for audio to arrive
+    stream.read_exact(&mut buf).unwrap();
+    stream.read_exact(&mut buf).unwrap();
+
+    let stats = stream.stats();
+    assert_eq!(stats.underruns, 1);
+    assert_eq!(stats.silence_bytes, 8000);
+    assert_eq!(stats.peak_fill, 1000);
+    assert!(stats.target > initial);
+
+    for _ in 0..20 {
+        stream.write_all(&audio(0, 10)).unwrap();
+        stream.read_exact(&mut buf).unwrap();
+    }
+
+    assert_eq!(stream.stats().underruns, 21);
+    assert_eq!(stream.capacity(), 128 * 1024);
+}
+
+#[test]
+fn silence_outside_of_playback_is_not_an_underrun() {
+    let mut stream = Stream::new();
+    let mut buf = [0; 4000];
+
+    // Nothing is playing yet
+    stream.read_exact(&mut buf).unwrap();
+
+    // Playback has started, but the first audio has yet to arrive
+    stream.set_active(true);
+    stream.read_exact(&mut buf).unwrap();
+
+    // Playback was paused after audio came through
+    stream.write_all(&audio(0, 1000)).unwrap();
+    assert_eq!(stream.read(&mut buf).unwrap(), 1000);
+    stream.set_active(false);
+    stream.read_exact(&mut buf).unwrap();
+
+    let stats = stream.stats();
+    assert_eq!(stats.underruns, 0);
+    assert_eq!(stats.silence_bytes, 0);
+}
+
+/// Move a few megabytes of audio from a writer thread to a reader thread, like librespot and songbird would
+fn transfer<S>(stream: S)
+where
+    S: Read + Write + Clone + Send + 'static,
+{
+    const TOTAL: usize = 4 * 1024 * 1024;
+
+    let mut writer = stream.clone();
+    let handle = thread::spawn(move || {
+        for offset in (0..TOTAL).step_by(4096) {
+            writer.write_all(&audio(offset, 4096)).unwrap();
+        }
+    });
+
+    let mut reader = stream;
+    let mut received = Vec::with_capacity(TOTAL);
+    let mut buf = [0; 3840];
+
+    while received.len() < TOTAL {
+        let len = reader.read(&mut buf).unwrap();
+
+        // Skip the silence that is produced while the writer is catching up
+        if buf[..len].iter().all(|&byte| byte == 0) {
+            thread::yield_now();
+            continue;
+        }
+
+        received.extend_from_slice(&buf[..len]);
+    }
+
+    handle.join().unwrap();
+    assert!(received == audio(0, TOTAL));
+}
+
+#[test]
+fn concurrent_transfer_preserves_audio() {
+    transfer(Stream::new());
+}
+
+#[test]
+fn reference_concurrent_transfer_preserves_audio() {
+    transfer(ReferenceStream::new());
+}
@@ -16,6 +16,64 @@ pub static SPOTIFY_CLIENT_SECRET: LazyLock<String> = LazyLock::new(|| {
         .expect("missing SPOTIFY_CLIENT_SECRET environment variable")
 });
 
+/// The highest bitrate (in kbps) that servers are allowed to stream at
+pub static MAX_BITRATE: LazyLock<u16> = LazyLock::new(|| {
+    std::env::var("MAX_BITRATE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(320)
+});
+
+/// How long (in seconds) the player is kept alive after the host leaves the call
+pub static HOST_GRACE_PERIOD: LazyLock<u64> = LazyLock::new(|| {
+    std::env::var("HOST_GRACE_PERIOD")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(60)
+});
+
+/// How long (in seconds) audio may be missing during playback before the player tries to recover
+pub static STALL_THRESHOLD: LazyLock<u64> = LazyLock::new(|| {
+    std::env::var("STALL_THRESHOLD")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(10)
+});
+
+/// The smallest (in kilobytes) the audio buffer of a player may shrink to
+pub static BUFFER_MIN_SIZE: LazyLock<usize> = LazyLock::new(|| {
+    std::env::var("BUFFER_MIN_SIZE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(32)
+});
+
+/// The largest (in kilobytes) the audio buffer of a player may grow to after underruns
+pub static BUFFER_MAX_SIZE: LazyLock<usize> = LazyLock::new(|| {
+    std::env::var("BUFFER_MAX_SIZE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(256)
+});
+
+/// How long (in milliseconds) audio fades in and out when playback starts, stops or jumps, zero disables fading
+pub static FADE_LENGTH: LazyLock<u64> = LazyLock::new(|| {
+    std::env::var("FADE_LENGTH")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(20)
+});
+
+/// The directory in which audio from Spotify is cached, caching is disabled if this isn't set
+pub static CACHE_DIR: LazyLock<Option<String>> = LazyLock::new(|| std::env::var("CACHE_DIR").ok());
+
+/// The maximum size (in megabytes) of the audio cache
+pub static CACHE_SIZE_LIMIT: LazyLock<Option<u64>> = LazyLock::new(|| {
+    std::env::var("CACHE_SIZE_LIMIT")
+        .ok()
+        .and_then(|value| value.parse().ok())
+});
+
 // Locked behind `stats` feature
 pub static KV_URL: LazyLock<String> =
     LazyLock::new(|| std::env::var("KV_URL").expect("missing KV_URL environment variable"));
@@ -31,6 +31,41 @@ pub fn link_url() -> &'static str {
     &env::LINK_URL
 }
 
+pub fn max_bitrate() -> u16 {
+    *env::MAX_BITRATE
+}
+
+/// The time the host has to rejoin the call before their player is shut down, zero disables the grace period
+pub fn host_grace_period() -> std::time::Duration {
+    std::time::Duration::from_secs(*env::HOST_GRACE_PERIOD)
+}
+
+/// How long audio may be missing during playback before the player tries to recover, zero disables the watchdog
+pub fn stall_threshold() -> std::time::Duration {
+    std::time::Duration::from_secs(*env::STALL_THRESHOLD)
+}
+
+/// The smallest and largest size (in bytes) the audio buffer of a player may have
+pub fn buffer_bounds() -> (usize, usize) {
+    let min = *env::BUFFER_MIN_SIZE * 1024;
+
+    (min, usize::max(*env::BUFFER_MAX_SIZE * 1024, min))
+}
+
+/// How long audio fades in and out to avoid clicks when playback starts, stops or jumps
+pub fn fade_length() -> std::time::Duration {
+    std::time::Duration::from_millis(*env::FADE_LENGTH)
+}
+
+pub fn cache_dir() -> Option<&'static str> {
+    env::CACHE_DIR.as_deref()
+}
+
+/// The maximum size of the audio cache in bytes, or `None` if the cache may grow indefinitely
+pub fn cache_size_limit() -> Option<u64> {
+    env::CACHE_SIZE_LIMIT.map(|megabytes| megabytes * 1024 * 1024)
+}
+
 pub fn kv_url() -> &'static str {
     &env::KV_URL
 }
@@ -0,0 +1 @@
+ALTER TABLE "user" DROP COLUMN auto_transfer;
@@ -0,0 +1 @@
+ALTER TABLE "user" ADD COLUMN auto_transfer BOOLEAN NOT NULL DEFAULT FALSE;
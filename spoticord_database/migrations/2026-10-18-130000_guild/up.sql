@@ -0,0 +1,5 @@
+CREATE TABLE "guild" (
+    id VARCHAR PRIMARY KEY,
+    queue_policy VARCHAR(16) NOT NULL DEFAULT 'anyone',
+    dj_role VARCHAR
+);
@@ -0,0 +1 @@
+ALTER TABLE "guild" ADD COLUMN bitrate SMALLINT NOT NULL DEFAULT 160;
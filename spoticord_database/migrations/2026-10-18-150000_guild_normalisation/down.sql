@@ -0,0 +1,5 @@
+ALTER TABLE "guild"
+    DROP COLUMN normalisation,
+    DROP COLUMN normalisation_mode,
+    DROP COLUMN normalisation_pregain,
+    DROP COLUMN normalisation_threshold;
@@ -0,0 +1,5 @@
+ALTER TABLE "guild"
+    ADD COLUMN normalisation BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN normalisation_mode VARCHAR(8) NOT NULL DEFAULT 'track',
+    ADD COLUMN normalisation_pregain DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN normalisation_threshold DOUBLE PRECISION NOT NULL DEFAULT -2;
@@ -0,0 +1 @@
+ALTER TABLE "guild" ADD COLUMN crossfade SMALLINT NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE "guild" ADD COLUMN autoplay BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,3 @@
+ALTER TABLE "guild"
+    DROP COLUMN equalizer_preset,
+    DROP COLUMN equalizer_gains;
@@ -0,0 +1,3 @@
+ALTER TABLE "guild"
+    ADD COLUMN equalizer_preset VARCHAR(16) NOT NULL DEFAULT 'flat',
+    ADD COLUMN equalizer_gains REAL[] NOT NULL DEFAULT '{0, 0, 0, 0, 0}';
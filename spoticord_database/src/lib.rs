@@ -4,6 +4,8 @@ mod migrations;
 mod models;
 mod schema;
 
+pub use models::{EqualizerPreset, NormalisationMode, QueuePolicy};
+
 use std::sync::Arc;
 
 use chrono::{Duration, Utc};
@@ -13,7 +15,7 @@ use diesel_async::{
     AsyncPgConnection, RunQueryDsl,
 };
 use error::*;
-use models::{Account, LinkRequest, User};
+use models::{Account, Guild, LinkRequest, User};
 use rand::{distributions::Alphanumeric, Rng};
 use rspotify::{clients::BaseClient, Token};
 
@@ -97,6 +99,169 @@ impl Database {
         Ok(())
     }
 
+    pub async fn update_auto_transfer(
+        &self,
+        user_id: impl AsRef<str>,
+        _auto_transfer: bool,
+    ) -> Result<()> {
+        use schema::user::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(user)
+            .filter(id.eq(user_id.as_ref()))
+            .set(auto_transfer.eq(_auto_transfer))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    // Guild operations
+
+    pub async fn get_guild(&self, guild_id: impl AsRef<str>) -> Result<Guild> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let result = guild
+            .filter(id.eq(guild_id.as_ref()))
+            .select(Guild::as_select())
+            .first(&mut connection)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn create_guild(&self, guild_id: impl AsRef<str>) -> Result<Guild> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let result = diesel::insert_into(guild)
+            .values(id.eq(guild_id.as_ref()))
+            .returning(Guild::as_returning())
+            .get_result(&mut connection)
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Retrieve the settings of a server, without creating them if the server never changed any
+    pub async fn get_guild_or_default(&self, guild_id: impl AsRef<str>) -> Result<Guild> {
+        match self.get_guild(&guild_id).await {
+            Err(DatabaseError::NotFound) => Ok(Guild::new(guild_id.as_ref())),
+            result => result,
+        }
+    }
+
+    pub async fn get_or_create_guild(&self, guild_id: impl AsRef<str>) -> Result<Guild> {
+        match self.get_guild(&guild_id).await {
+            Err(DatabaseError::NotFound) => self.create_guild(guild_id).await,
+            result => result,
+        }
+    }
+
+    pub async fn update_queue_policy(
+        &self,
+        guild_id: impl AsRef<str>,
+        policy: QueuePolicy,
+        role: Option<String>,
+    ) -> Result<()> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(guild)
+            .filter(id.eq(guild_id.as_ref()))
+            .set((queue_policy.eq(policy.as_str()), dj_role.eq(role)))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_bitrate(&self, guild_id: impl AsRef<str>, kbps: i16) -> Result<()> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(guild)
+            .filter(id.eq(guild_id.as_ref()))
+            .set(bitrate.eq(kbps))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_normalisation(
+        &self,
+        guild_id: impl AsRef<str>,
+        enabled: bool,
+        mode: NormalisationMode,
+        pregain: f64,
+        threshold: f64,
+    ) -> Result<()> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(guild)
+            .filter(id.eq(guild_id.as_ref()))
+            .set((
+                normalisation.eq(enabled),
+                normalisation_mode.eq(mode.as_str()),
+                normalisation_pregain.eq(pregain),
+                normalisation_threshold.eq(threshold),
+            ))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_crossfade(&self, guild_id: impl AsRef<str>, seconds: i16) -> Result<()> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(guild)
+            .filter(id.eq(guild_id.as_ref()))
+            .set(crossfade.eq(seconds))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_autoplay(&self, guild_id: impl AsRef<str>, enabled: bool) -> Result<()> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(guild)
+            .filter(id.eq(guild_id.as_ref()))
+            .set(autoplay.eq(enabled))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_equalizer(
+        &self,
+        guild_id: impl AsRef<str>,
+        preset: EqualizerPreset,
+        gains: &[f32],
+    ) -> Result<()> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(guild)
+            .filter(id.eq(guild_id.as_ref()))
+            .set((
+                equalizer_preset.eq(preset.as_str()),
+                equalizer_gains.eq(gains.iter().copied().map(Some).collect::<Vec<_>>()),
+            ))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
     // Account operations
 
     pub async fn get_account(&self, _user_id: impl AsRef<str>) -> Result<Account> {
@@ -7,6 +7,7 @@ use diesel::prelude::*;
 pub struct User {
     pub id: String,
     pub device_name: String,
+    pub auto_transfer: bool,
 }
 
 #[derive(Queryable, Selectable, Debug)]
@@ -49,3 +50,163 @@ impl LinkRequest {
         Utc::now().naive_utc() > self.expires - offset
     }
 }
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = super::schema::guild)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Guild {
+    pub id: String,
+    pub queue_policy: String,
+    pub dj_role: Option<String>,
+
+    /// The preferred streaming bitrate in kbps
+    pub bitrate: i16,
+
+    pub normalisation: bool,
+    pub normalisation_mode: String,
+    pub normalisation_pregain: f64,
+    pub normalisation_threshold: f64,
+
+    /// The crossfade between tracks in seconds
+    pub crossfade: i16,
+
+    /// Continue with related tracks once the playing context ends
+    pub autoplay: bool,
+
+    pub equalizer_preset: String,
+
+    /// The gain (in dB) of every band, only used by the custom preset
+    pub equalizer_gains: Vec<Option<f32>>,
+}
+
+impl Guild {
+    /// The settings of a server that never changed them, which match the column defaults
+    pub fn new(guild_id: impl Into<String>) -> Self {
+        Self {
+            id: guild_id.into(),
+            queue_policy: QueuePolicy::default().as_str().to_string(),
+            dj_role: None,
+            bitrate: 160,
+            normalisation: false,
+            normalisation_mode: NormalisationMode::default().as_str().to_string(),
+            normalisation_pregain: 0.0,
+            normalisation_threshold: -2.0,
+            crossfade: 0,
+            autoplay: false,
+            equalizer_preset: EqualizerPreset::default().as_str().to_string(),
+            equalizer_gains: vec![Some(0.0); 5],
+        }
+    }
+
+    pub fn queue_policy(&self) -> QueuePolicy {
+        QueuePolicy::from_str(&self.queue_policy)
+    }
+
+    pub fn normalisation_mode(&self) -> NormalisationMode {
+        NormalisationMode::from_str(&self.normalisation_mode)
+    }
+
+    pub fn equalizer_preset(&self) -> EqualizerPreset {
+        EqualizerPreset::from_str(&self.equalizer_preset)
+    }
+
+    /// The custom gain of every band, missing bands are left flat
+    pub fn equalizer_gains(&self) -> Vec<f32> {
+        self.equalizer_gains
+            .iter()
+            .map(|gain| gain.unwrap_or_default())
+            .collect()
+    }
+}
+
+/// Decides who is allowed to add songs to the queue of the host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueuePolicy {
+    #[default]
+    Anyone,
+    DjRole,
+    HostOnly,
+}
+
+impl QueuePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Anyone => "anyone",
+            Self::DjRole => "dj_role",
+            Self::HostOnly => "host_only",
+        }
+    }
+
+    /// Unknown values fall back to the default policy
+    fn from_str(value: &str) -> Self {
+        match value {
+            "dj_role" => Self::DjRole,
+            "host_only" => Self::HostOnly,
+            _ => Self::Anyone,
+        }
+    }
+}
+
+/// Whether loudness normalisation is based on the loudness of single tracks or of entire albums
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NormalisationMode {
+    #[default]
+    Track,
+    Album,
+}
+
+impl NormalisationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Track => "track",
+            Self::Album => "album",
+        }
+    }
+
+    /// Unknown values fall back to the default mode
+    fn from_str(value: &str) -> Self {
+        match value {
+            "album" => Self::Album,
+            _ => Self::Track,
+        }
+    }
+}
+
+/// Which sound the equalizer is tuned to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EqualizerPreset {
+    #[default]
+    Flat,
+    BassBoost,
+    Vocal,
+    Treble,
+    Loudness,
+
+    /// Uses the gains that were configured for the guild
+    Custom,
+}
+
+impl EqualizerPreset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flat => "flat",
+            Self::BassBoost => "bass_boost",
+            Self::Vocal => "vocal",
+            Self::Treble => "treble",
+            Self::Loudness => "loudness",
+            Self::Custom => "custom",
+        }
+    }
+
+    /// Unknown values fall back to the default preset
+    fn from_str(value: &str) -> Self {
+        match value {
+            "bass_boost" => Self::BassBoost,
+            "vocal" => Self::Vocal,
+            "treble" => Self::Treble,
+            "loudness" => Self::Loudness,
+            "custom" => Self::Custom,
+            _ => Self::Flat,
+        }
+    }
+}
@@ -16,6 +16,26 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    guild (id) {
+        id -> Varchar,
+        #[max_length = 16]
+        queue_policy -> Varchar,
+        dj_role -> Nullable<Varchar>,
+        bitrate -> Int2,
+        normalisation -> Bool,
+        #[max_length = 8]
+        normalisation_mode -> Varchar,
+        normalisation_pregain -> Float8,
+        normalisation_threshold -> Float8,
+        crossfade -> Int2,
+        autoplay -> Bool,
+        #[max_length = 16]
+        equalizer_preset -> Varchar,
+        equalizer_gains -> Array<Nullable<Float4>>,
+    }
+}
+
 diesel::table! {
     link_request (token) {
         token -> Text,
@@ -29,6 +49,7 @@ diesel::table! {
         id -> Varchar,
         #[max_length = 32]
         device_name -> Varchar,
+        auto_transfer -> Bool,
     }
 }
 
@@ -37,6 +58,7 @@ diesel::joinable!(link_request -> user (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     account,
+    guild,
     link_request,
     user,
 );
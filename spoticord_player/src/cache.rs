@@ -0,0 +1,166 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use librespot::{
+    core::{cache::Cache, FileId, Session as SpotifySession, SpotifyId},
+    metadata::audio::AudioItem,
+};
+use log::{error, trace};
+
+/// An on-disk cache of audio files that is shared between all players.
+///
+/// The audio directory is kept below the configured size limit by evicting the least recently used files.
+/// Credentials are not cached here, as those are stored per user in the database.
+#[derive(Clone)]
+pub struct AudioCache {
+    cache: Cache,
+    audio_location: PathBuf,
+    stats: Arc<Stats>,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bytes_saved: AtomicU64,
+}
+
+/// A snapshot of how well the cache is doing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+
+    /// The amount of bytes that did not have to be downloaded from Spotify
+    pub bytes_saved: u64,
+
+    /// The amount of bytes that are currently stored on disk
+    pub size: u64,
+}
+
+impl CacheStats {
+    /// The fraction of tracks that were played from the cache, between 0 and 1
+    pub fn hit_ratio(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl AudioCache {
+    /// Open (or create) the cache in the given directory, optionally limiting the size of the audio files in bytes
+    pub fn new(
+        directory: impl AsRef<Path>,
+        size_limit: Option<u64>,
+    ) -> Result<Self, librespot::core::Error> {
+        let directory = directory.as_ref();
+        let audio_location = directory.join("audio");
+
+        let cache = Cache::new(
+            None::<PathBuf>,
+            Some(directory.join("volume")),
+            Some(&audio_location),
+            size_limit,
+        )?;
+
+        Ok(Self {
+            cache,
+            audio_location,
+            stats: Arc::default(),
+        })
+    }
+
+    pub(crate) fn cache(&self) -> Cache {
+        self.cache.clone()
+    }
+
+    /// Check whether the audio of a track that is about to be loaded is already on disk.
+    ///
+    /// This has to happen before librespot finishes downloading the track, as it is written to the cache afterwards.
+    pub(crate) async fn record(&self, session: &SpotifySession, track_id: SpotifyId) {
+        let item = match AudioItem::get_file(session, track_id).await {
+            Ok(item) => item,
+            Err(why) => {
+                trace!("Failed to retrieve metadata for cache statistics: {why}");
+                return;
+            }
+        };
+
+        let cached = item
+            .files
+            .values()
+            .filter_map(|file| self.cache.file_path(*file))
+            .find_map(|path| std::fs::metadata(path).ok());
+
+        match cached {
+            Some(metadata) => {
+                self.stats.hits.fetch_add(1, Ordering::Relaxed);
+                self.stats
+                    .bytes_saved
+                    .fetch_add(metadata.len(), Ordering::Relaxed);
+            }
+            None => {
+                self.stats.misses.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.stats.hits.load(Ordering::Relaxed),
+            misses: self.stats.misses.load(Ordering::Relaxed),
+            bytes_saved: self.stats.bytes_saved.load(Ordering::Relaxed),
+            size: self.files().iter().map(|(_, size)| size).sum(),
+        }
+    }
+
+    /// Remove all audio files from the cache, returning the amount of bytes that were freed
+    pub fn clear(&self) -> u64 {
+        let mut freed = 0;
+
+        for (file, size) in self.files() {
+            // Removing through librespot keeps the size limiter up to date
+            match self.cache.remove_file(file) {
+                Ok(()) => freed += size,
+                Err(why) => error!("Failed to remove cached file: {why}"),
+            }
+        }
+
+        freed
+    }
+
+    /// All audio files in the cache, along with their size.
+    ///
+    /// Librespot stores every file as `<first two hex digits>/<remaining hex digits>` of its file id.
+    fn files(&self) -> Vec<(FileId, u64)> {
+        let Ok(directories) = std::fs::read_dir(&self.audio_location) else {
+            return vec![];
+        };
+
+        directories
+            .flatten()
+            .filter_map(|directory| {
+                Some((directory.file_name(), directory.path().read_dir().ok()?))
+            })
+            .flat_map(|(prefix, entries)| {
+                entries.flatten().filter_map(move |entry| {
+                    let name = format!(
+                        "{}{}",
+                        prefix.to_string_lossy(),
+                        entry.file_name().to_string_lossy()
+                    );
+                    let id = hex::decode(name).ok().filter(|id| id.len() == 20)?;
+                    let size = entry.metadata().ok()?.len();
+
+                    Some((FileId::from_raw(&id), size))
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt::Display;
+
+use librespot::core::{spotify_id::SpotifyItemType, SpotifyId};
+
+/// A track that Spotify was unable to play
+#[derive(Debug, Clone)]
+pub struct PlaybackError {
+    pub track_id: SpotifyId,
+
+    /// The name of the track, if its metadata could be retrieved
+    pub name: Option<String>,
+    pub reason: PlaybackErrorReason,
+}
+
+impl PlaybackError {
+    pub fn url(&self) -> Option<String> {
+        let id = self.track_id.to_base62().ok()?;
+        let kind = match self.track_id.item_type {
+            SpotifyItemType::Episode => "episode",
+            _ => "track",
+        };
+
+        Some(format!("https://open.spotify.com/{kind}/{id}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackErrorReason {
+    /// The track cannot be played in the country of the host, or has been restricted otherwise
+    Restricted,
+
+    /// The track does not exist (anymore)
+    NotFound,
+
+    /// The audio of the track could not be retrieved or decrypted
+    AudioUnavailable,
+}
+
+impl Display for PlaybackErrorReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Restricted => write!(f, "it is not available in the country of the host"),
+            Self::NotFound => write!(f, "it has been removed from Spotify"),
+            Self::AudioUnavailable => write!(f, "Spotify was unable to provide the audio"),
+        }
+    }
+}
+
+/// Why the player could not carry out a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerError {
+    /// Nothing is playing on this device, so there is nothing to control
+    NoActiveDevice,
+
+    /// The player has shut down, or is shutting down
+    Shutdown,
+
+    /// Spotify Connect refused to carry out the command
+    Rejected(String),
+
+    /// The player did not respond in time
+    TimedOut,
+}
+
+impl Display for PlayerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoActiveDevice => write!(f, "Spoticord is not playing anything right now"),
+            Self::Shutdown => write!(f, "The player has stopped"),
+            Self::Rejected(reason) => write!(f, "Spotify rejected the request ({reason})"),
+            Self::TimedOut => write!(f, "The player took too long to respond"),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+impl From<librespot::core::Error> for PlayerError {
+    fn from(value: librespot::core::Error) -> Self {
+        Self::Rejected(value.to_string())
+    }
+}
@@ -1,30 +1,110 @@
 use std::collections::HashSet;
 
 use librespot::{
-    core::SpotifyId,
+    core::{date::Date, SpotifyId},
     metadata::{
         artist::ArtistsWithRole,
-        audio::{AudioItem, UniqueFields},
+        audio::{AudioItem, CoverImage, UniqueFields},
     },
+    playback::config::Bitrate,
 };
 
+/// The image that is shown for items that don't have any cover art
+pub const FALLBACK_THUMBNAIL: &str = "https://spoticord.com/spotify-logo.png";
+
+/// The smallest width (in pixels) of a cover that is still sharp enough to use as a thumbnail
+const THUMBNAIL_WIDTH: i32 = 300;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepeatMode {
+    #[default]
+    Off,
+
+    /// Repeat the entire playlist, album, etc.
+    Context,
+
+    /// Repeat the current track
+    Track,
+}
+
+/// The bitrate at which audio is streamed from Spotify
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quality {
+    /// 96kbps
+    Low,
+
+    /// 160kbps
+    #[default]
+    Normal,
+
+    /// 320kbps
+    High,
+}
+
+impl Quality {
+    /// Pick the highest quality that does not exceed the given bitrate (in kbps)
+    pub fn from_kbps(kbps: u16) -> Self {
+        match kbps {
+            0..=159 => Self::Low,
+            160..=319 => Self::Normal,
+            _ => Self::High,
+        }
+    }
+
+    pub fn kbps(&self) -> u16 {
+        match self {
+            Self::Low => 96,
+            Self::Normal => 160,
+            Self::High => 320,
+        }
+    }
+
+    pub(crate) fn bitrate(&self) -> Bitrate {
+        match self {
+            Self::Low => Bitrate::Bitrate96,
+            Self::Normal => Bitrate::Bitrate160,
+            Self::High => Bitrate::Bitrate320,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PlaybackInfo {
     audio_item: AudioItem,
+    release_date: Option<Date>,
 
     updated_at: u128,
     position: u32,
     playing: bool,
+    volume: u16,
+    shuffle: bool,
+    repeat: RepeatMode,
+    quality: Quality,
+
+    /// How fast the audio plays relative to normal speed
+    speed: f32,
 }
 
 impl PlaybackInfo {
-    pub fn new(audio_item: AudioItem, position: u32, playing: bool) -> Self {
+    pub fn new(
+        audio_item: AudioItem,
+        position: u32,
+        playing: bool,
+        volume: u16,
+        quality: Quality,
+    ) -> Self {
         Self {
             audio_item,
+            release_date: None,
 
             updated_at: spoticord_utils::get_time(),
             position,
             playing,
+            volume,
+            shuffle: false,
+            repeat: RepeatMode::Off,
+            quality,
+            speed: 1.0,
         }
     }
 
@@ -32,11 +112,8 @@ impl PlaybackInfo {
         self.audio_item.track_id
     }
 
-    pub fn track_id_string(&self) -> String {
-        self.audio_item
-            .track_id
-            .to_base62()
-            .expect("invalid spotify id")
+    pub fn track_id_string(&self) -> Option<String> {
+        self.audio_item.track_id.to_base62().ok()
     }
 
     pub fn name(&self) -> String {
@@ -72,36 +149,103 @@ impl PlaybackInfo {
         }
     }
 
+    pub fn album_artists(&self) -> Option<Vec<String>> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { .. } => None,
+            UniqueFields::Track { album_artists, .. } => Some(album_artists.clone()),
+        }
+    }
+
+    /// The position of the track on its disc
+    pub fn track_number(&self) -> Option<u32> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { .. } => None,
+            UniqueFields::Track { number, .. } => Some(*number),
+        }
+    }
+
+    pub fn disc_number(&self) -> Option<u32> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { .. } => None,
+            UniqueFields::Track { disc_number, .. } => Some(*disc_number),
+        }
+    }
+
+    /// The popularity of the track, between 0 and 100
+    pub fn popularity(&self) -> Option<u8> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { .. } => None,
+            UniqueFields::Track { popularity, .. } => Some(*popularity),
+        }
+    }
+
+    pub fn description(&self) -> Option<String> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { description, .. } => Some(description.to_string()),
+            UniqueFields::Track { .. } => None,
+        }
+    }
+
+    pub fn publisher(&self) -> Option<String> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { publisher, .. } => Some(publisher.to_string()),
+            UniqueFields::Track { .. } => None,
+        }
+    }
+
+    pub fn explicit(&self) -> bool {
+        self.audio_item.is_explicit
+    }
+
+    /// The release date of the album of a track, or the publish date of an episode
+    pub fn release_date(&self) -> Option<&Date> {
+        self.release_date.as_ref()
+    }
+
+    pub fn release_year(&self) -> Option<i32> {
+        self.release_date.as_ref().map(|date| date.year())
+    }
+
+    /// All sizes in which the cover art is available
+    pub fn covers(&self) -> &[CoverImage] {
+        &self.audio_item.covers
+    }
+
+    /// The smallest cover that is large enough to be shown as a thumbnail, or a fallback image if there is none
     pub fn thumbnail(&self) -> String {
-        self.audio_item
-            .covers
-            .first()
-            .expect("spotify track missing cover image")
-            .url
-            .to_string()
+        let covers = &self.audio_item.covers;
+
+        covers
+            .iter()
+            .filter(|cover| cover.width >= THUMBNAIL_WIDTH)
+            .min_by_key(|cover| cover.width)
+            .or_else(|| covers.iter().max_by_key(|cover| cover.width))
+            .map(|cover| cover.url.to_string())
+            .unwrap_or_else(|| FALLBACK_THUMBNAIL.to_string())
     }
 
     pub fn duration(&self) -> u32 {
         self.audio_item.duration_ms
     }
 
-    pub fn url(&self) -> String {
-        match &self.audio_item.unique_fields {
-            UniqueFields::Episode { .. } => format!(
-                "https://open.spotify.com/episode/{}",
-                self.track_id_string()
-            ),
-            UniqueFields::Track { .. } => {
-                format!("https://open.spotify.com/track/{}", self.track_id_string())
-            }
-        }
+    pub fn url(&self) -> Option<String> {
+        let id = self.track_id_string()?;
+
+        let url = match &self.audio_item.unique_fields {
+            UniqueFields::Episode { .. } => format!("https://open.spotify.com/episode/{id}"),
+            UniqueFields::Track { .. } => format!("https://open.spotify.com/track/{id}"),
+        };
+
+        Some(url)
     }
 
     /// Get the current playback position, which accounts for time that may have passed since this struct was last updated
+    ///
+    /// When playing faster or slower than normal, the track progresses by more or less than the time that passed.
     pub fn current_position(&self) -> u32 {
         if self.playing {
             let now = spoticord_utils::get_time();
-            let diff = now - self.updated_at;
+            let diff = (now - self.updated_at) as f64 * self.speed as f64;
 
             self.position + diff as u32
         } else {
@@ -113,6 +257,51 @@ impl PlaybackInfo {
         self.playing
     }
 
+    pub fn volume(&self) -> u16 {
+        self.volume
+    }
+
+    /// Get the current volume as a percentage (0-100)
+    pub fn volume_percent(&self) -> u8 {
+        (self.volume as u32 * 100 / u16::MAX as u32) as u8
+    }
+
+    pub fn update_volume(&mut self, volume: u16) {
+        self.volume = volume;
+    }
+
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+
+    pub fn update_shuffle(&mut self, shuffle: bool) {
+        self.shuffle = shuffle;
+    }
+
+    pub fn repeat(&self) -> RepeatMode {
+        self.repeat
+    }
+
+    pub fn update_repeat(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
+    /// The bitrate at which the player is streaming
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn update_speed(&mut self, speed: f32) {
+        // Time that passed so far was played at the previous speed
+        self.position = self.current_position();
+        self.updated_at = spoticord_utils::get_time();
+        self.speed = speed;
+    }
+
     pub fn update_playback(&mut self, position: u32, playing: bool) {
         self.position = position;
         self.playing = playing;
@@ -121,6 +310,11 @@ impl PlaybackInfo {
 
     pub fn update_track(&mut self, audio_item: AudioItem) {
         self.audio_item = audio_item;
+        self.release_date = None;
+    }
+
+    pub fn update_release_date(&mut self, release_date: Option<Date>) {
+        self.release_date = release_date;
     }
 
     pub fn is_episode(&self) -> bool {
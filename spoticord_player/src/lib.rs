@@ -1,43 +1,107 @@
+pub mod cache;
+pub mod error;
 pub mod info;
+pub mod link;
+
+pub use spoticord_audio::{
+    equalizer,
+    filter::{AudioFilter, FilterChain},
+    speed,
+    stream::{BufferBounds, StreamStats},
+};
 
 use anyhow::Result;
-use info::PlaybackInfo;
+use cache::AudioCache;
+use error::{PlaybackError, PlaybackErrorReason, PlayerError};
+use info::{PlaybackInfo, Quality, RepeatMode};
 use librespot::{
-    connect::{config::ConnectConfig, spirc::Spirc},
+    connect::{
+        config::ConnectConfig,
+        spirc::{Spirc, SpircLoadCommand},
+    },
     core::{
-        connection::AuthenticationError, http_client::HttpClientError, Session as SpotifySession,
-        SessionConfig,
+        connection::AuthenticationError, date::Date, error::ErrorKind,
+        http_client::HttpClientError, spotify_id::SpotifyItemType, Session as SpotifySession,
+        SessionConfig, SpotifyId,
     },
     discovery::Credentials,
-    metadata::Lyrics,
+    metadata::{
+        audio::{AudioFileFormat, AudioItem, UniqueFields},
+        Album, Artist, Episode, Lyrics, Metadata, Playlist, Show, Track,
+    },
     playback::{
-        config::{Bitrate, PlayerConfig, VolumeCtrl},
-        mixer::{self, MixerConfig},
+        config::{NormalisationType, PlayerConfig, VolumeCtrl},
+        mixer::{self, Mixer, MixerConfig},
         player::{Player as SpotifyPlayer, PlayerEvent as SpotifyPlayerEvent},
     },
+    protocol::spirc::TrackRef,
 };
-use log::{error, trace};
+use log::{error, trace, warn};
 use songbird::{input::RawAdapter, tracks::TrackHandle, Call};
 use spoticord_audio::{
+    crossfade::Crossfade,
+    equalizer::{Equalizer, Preset},
+    filter::Filters,
     sink::{SinkEvent, StreamSink},
+    speed::{Speed, SpeedMode},
     stream::Stream,
 };
 use std::{
     io::Write,
     sync::{atomic::AtomicBool, Arc},
+    time::Duration,
 };
 use tokio::sync::{mpsc, oneshot, Mutex};
 
+/// How often the player checks whether audio is still reaching Discord
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for the player to acknowledge a command
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for the player to load something, which involves retrieving its metadata first
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Reply = oneshot::Sender<Result<(), PlayerError>>;
+
 #[derive(Debug)]
 enum PlayerCommand {
-    NextTrack,
-    PreviousTrack,
-    Pause,
-    Play,
+    NextTrack(Reply),
+    PreviousTrack(Reply),
+    Pause(Reply),
+    Play(Reply),
+    SetVolume(u16, Reply),
+    Seek(u32, Reply),
+    SetShuffle(bool, Reply),
+    SetRepeat(RepeatMode, Reply),
+    SetSpeed(f32, Reply),
+
+    /// Seek to the current position, which makes librespot fetch the audio again
+    Nudge(Reply),
+
+    /// Report [`PlayerEvent::Recovered`] once audio flows, as this player replaces one that stalled
+    TakeOverStall(Reply),
+    Load(SpotifyId, Reply),
+    Resume(Box<PlaybackInfo>, u32, Reply),
 
     GetPlaybackInfo(oneshot::Sender<Option<PlaybackInfo>>),
+    GetVolume(oneshot::Sender<u16>),
     GetLyrics(oneshot::Sender<Option<Lyrics>>),
 
+    /// The tracks of a context that is being loaded, which were retrieved in the background
+    ContextResolved(SpotifyId, Result<Vec<SpotifyId>, PlayerError>, Reply),
+
+    /// The release date of the given track, which was retrieved in the background
+    ReleaseDate(SpotifyId, Date),
+
+    /// Why a track could not be played, which was figured out in the background
+    PlaybackError {
+        error: PlaybackError,
+
+        /// Whether the track should have been playable at 96kbps, which points at an audio key error
+        low_bitrate: bool,
+    },
+
     Shutdown,
 }
 
@@ -47,21 +111,148 @@ pub enum PlayerEvent {
     Play,
     Stopped,
     TrackChanged(Box<PlaybackInfo>),
+    VolumeChanged(u16),
+    Seeked(u32),
+    ShuffleChanged(bool),
+    RepeatChanged(RepeatMode),
+    SpeedChanged(f32),
+
+    /// The release date of the current track has arrived, shortly after [`PlayerEvent::TrackChanged`]
+    ReleaseDateRetrieved,
+
+    /// The given track has played until its end
+    EndOfTrack(SpotifyId),
+
+    /// The given track is being loaded in advance, as it will be played next
+    Preloading(SpotifyId),
+
+    /// The given track cannot be played, and will be skipped by Spotify
+    Unavailable(SpotifyId),
+
+    /// Why a track could not be played, sent after [`PlayerEvent::Unavailable`]
+    PlaybackError(PlaybackError),
+
+    /// The given track could not be played at 96kbps, which happens because of audio key errors
+    QualityUnavailable(PlaybackError),
+
+    /// A different Spotify client has taken control over this device
+    SessionClientChanged {
+        client_id: String,
+        client_name: String,
+    },
     ConnectionReset,
+
+    /// No audio has reached Discord for a while, even though Spotify is playing
+    Stalled(Duration),
+
+    /// Audio is flowing again after the player stalled
+    Recovered,
+}
+
+/// Options that are used when creating a [`Player`]
+#[derive(Debug, Clone)]
+pub struct PlayerSettings {
+    pub quality: Quality,
+
+    /// Loudness normalisation, disabled if `None`
+    pub normalisation: Option<Normalisation>,
+
+    /// How long tracks overlap when one ends and the next one starts
+    pub crossfade: Duration,
+
+    /// How long audio fades in and out when playback starts, stops or jumps, which prevents clicks
+    pub fade: Duration,
+
+    /// Reuse an existing Spotify Connect device ID, so that clients see the same device
+    pub device_id: Option<String>,
+
+    /// How long audio may be missing during playback before the player reports a stall, zero disables this
+    pub stall_threshold: Duration,
+
+    /// Continue with related tracks once the playing context ends
+    pub autoplay: bool,
+
+    /// How large the audio buffer may become while it adapts to underruns
+    pub buffer: BufferBounds,
+
+    pub equalizer: Preset,
+
+    /// How fast audio plays relative to normal speed
+    pub speed: f32,
+}
+
+impl Default for PlayerSettings {
+    fn default() -> Self {
+        Self {
+            quality: Quality::default(),
+            normalisation: None,
+            crossfade: Duration::ZERO,
+            fade: Duration::ZERO,
+            device_id: None,
+            stall_threshold: Duration::ZERO,
+            autoplay: false,
+            buffer: BufferBounds::default(),
+            equalizer: Preset::default(),
+            speed: 1.0,
+        }
+    }
+}
+
+impl PlayerSettings {
+    /// The filters that audio runs through before it is sent to Discord
+    pub fn filter_chain(&self) -> FilterChain {
+        let mut chain = FilterChain::new();
+
+        if !self.equalizer.is_flat() {
+            chain.push(Equalizer::new(self.equalizer));
+        }
+
+        chain
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Normalisation {
+    /// Keep the loudness differences between tracks of the same album intact
+    pub album: bool,
+    pub pregain_db: f64,
+
+    /// The level above which the limiter kicks in
+    pub threshold_dbfs: f64,
 }
 
 pub struct Player {
     session: SpotifySession,
     spirc: Spirc,
+    mixer: Arc<dyn Mixer>,
     track: TrackHandle,
     stream: Stream,
+    crossfade: Crossfade,
+    speed: Speed,
+    cache: Option<AudioCache>,
+
+    /// The track that was most recently loaded in advance, which has already been checked against the cache
+    preloaded: Option<SpotifyId>,
 
     playback_info: Option<PlaybackInfo>,
+    shuffle: bool,
+    repeat: RepeatMode,
+    quality: Quality,
+
+    stall_threshold: Duration,
+
+    /// How long audio has been missing since the last stall report
+    stalled_for: Duration,
+
+    /// Whether a stall has been reported and audio hasn't recovered yet
+    stalled: bool,
 
     // Communication
     events: mpsc::Sender<PlayerEvent>,
 
     commands: mpsc::Receiver<PlayerCommand>,
+    commands_inner_tx: mpsc::Sender<PlayerCommand>,
+    commands_inner_rx: mpsc::Receiver<PlayerCommand>,
     spotify_events: mpsc::UnboundedReceiver<SpotifyPlayerEvent>,
     sink_events: mpsc::UnboundedReceiver<SinkEvent>,
 
@@ -74,11 +265,16 @@ impl Player {
         credentials: Credentials,
         call: Arc<Mutex<Call>>,
         device_name: impl Into<String>,
+        settings: PlayerSettings,
+        cache: Option<AudioCache>,
     ) -> Result<(PlayerHandle, mpsc::Receiver<PlayerEvent>, Vec<u8>), librespot::core::Error> {
         let (event_tx, event_rx) = mpsc::channel(16);
 
         let mut call_lock = call.lock().await;
-        let stream = Stream::new();
+        let stream = Stream::with_bounds(settings.buffer);
+        stream.set_fade(settings.fade);
+        let filters = Filters::new(settings.filter_chain());
+        let speed = Speed::new(settings.speed);
 
         // Create songbird audio track
         let adapter = RawAdapter::new(stream.clone(), 44100, 2);
@@ -89,28 +285,50 @@ impl Player {
         drop(call_lock);
 
         // Create librespot audio streamer
-        let session = SpotifySession::new(SessionConfig::default(), None);
+        let mut session_config = SessionConfig::default();
+        if let Some(device_id) = settings.device_id {
+            session_config.device_id = device_id;
+        }
+
+        session_config.autoplay = Some(settings.autoplay);
+
+        let session = SpotifySession::new(session_config, cache.as_ref().map(AudioCache::cache));
         let mixer = (mixer::find(Some("softvol")).expect("missing softvol mixer"))(MixerConfig {
             volume_ctrl: VolumeCtrl::Log(VolumeCtrl::DEFAULT_DB_RANGE),
             ..Default::default()
         });
 
+        let mut player_config = PlayerConfig {
+            bitrate: settings.quality.bitrate(),
+            ..Default::default()
+        };
+
+        if let Some(normalisation) = settings.normalisation {
+            player_config.normalisation = true;
+            player_config.normalisation_type = if normalisation.album {
+                NormalisationType::Album
+            } else {
+                NormalisationType::Track
+            };
+            player_config.normalisation_pregain_db = normalisation.pregain_db;
+            player_config.normalisation_threshold_dbfs = normalisation.threshold_dbfs;
+        }
+
+        let crossfade = Crossfade::new(settings.crossfade);
+
         let (tx_sink, rx_sink) = mpsc::unbounded_channel();
-        let player = SpotifyPlayer::new(
-            PlayerConfig {
-                // 96kbps causes audio key errors, so enjoy the quality upgrade
-                bitrate: Bitrate::Bitrate160,
-                ..Default::default()
-            },
-            session.clone(),
-            mixer.get_soft_volume(),
-            {
-                let stream = stream.clone();
-                move || Box::new(StreamSink::new(stream, tx_sink))
-            },
-        );
+        let player = SpotifyPlayer::new(player_config, session.clone(), mixer.get_soft_volume(), {
+            let stream = stream.clone();
+            let crossfade = crossfade.clone();
+            let filters = filters.clone();
+            let speed = speed.clone();
+            move || Box::new(StreamSink::new(stream, tx_sink, crossfade, filters, speed))
+        });
         let rx_player = player.get_player_event_channel();
 
+        // The sink picks up track changes itself, so that they line up with the audio it receives
+        crossfade.listen(player.get_player_event_channel());
+
         let device_name = device_name.into();
         let mut tries = 0;
 
@@ -154,20 +372,35 @@ impl Player {
 
         // Keep auth data to reuse later for faster reconnections and less authentication requests to Spotify
         let auth_data = session.auth_data();
+        let device_id = session.device_id().to_string();
 
         let shutdown = Arc::new(AtomicBool::new(false));
         let (tx, rx) = mpsc::channel(16);
+        let (inner_tx, inner_rx) = mpsc::channel(16);
         let player = Self {
             session,
             spirc,
+            mixer,
             track,
-            stream,
+            stream: stream.clone(),
+            crossfade,
+            speed,
+            cache,
+            preloaded: None,
 
             playback_info: None,
+            shuffle: false,
+            repeat: RepeatMode::Off,
+            quality: settings.quality,
+            stall_threshold: settings.stall_threshold,
+            stalled_for: Duration::ZERO,
+            stalled: false,
 
             events: event_tx.clone(),
 
             commands: rx,
+            commands_inner_tx: inner_tx,
+            commands_inner_rx: inner_rx,
             spotify_events: rx_player,
             sink_events: rx_sink,
 
@@ -185,10 +418,21 @@ impl Player {
         });
         tokio::spawn(player.run());
 
-        Ok((PlayerHandle { commands: tx }, event_rx, auth_data))
+        Ok((
+            PlayerHandle {
+                commands: tx,
+                device_id,
+                stream,
+                filters,
+            },
+            event_rx,
+            auth_data,
+        ))
     }
 
     async fn run(mut self) {
+        let mut watchdog = tokio::time::interval(WATCHDOG_INTERVAL);
+
         loop {
             tokio::select! {
                 opt_command = self.commands.recv() => {
@@ -200,6 +444,11 @@ impl Player {
                     self.handle_command(command).await;
                 },
 
+                // Results of work that was done in the background
+                Some(command) = self.commands_inner_rx.recv() => {
+                    self.handle_command(command).await;
+                },
+
                 Some(event) = self.spotify_events.recv() => {
                     self.handle_spotify_event(event).await;
                 },
@@ -208,6 +457,10 @@ impl Player {
                     self.handle_sink_event(event).await;
                 }
 
+                _ = watchdog.tick() => {
+                    self.check_stall().await;
+                }
+
                 else => break,
             }
         }
@@ -220,14 +473,51 @@ impl Player {
 
     async fn handle_command(&mut self, command: PlayerCommand) {
         match command {
-            PlayerCommand::NextTrack => _ = self.spirc.next(),
-            PlayerCommand::PreviousTrack => _ = self.spirc.prev(),
-            PlayerCommand::Pause => _ = self.spirc.pause(),
-            PlayerCommand::Play => _ = self.spirc.play(),
+            PlayerCommand::NextTrack(tx) => _ = tx.send(self.control(Spirc::next)),
+            PlayerCommand::PreviousTrack(tx) => _ = tx.send(self.control(Spirc::prev)),
+            PlayerCommand::Pause(tx) => _ = tx.send(self.control(Spirc::pause)),
+            PlayerCommand::Play(tx) => _ = tx.send(self.control(Spirc::play)),
+            PlayerCommand::SetVolume(volume, tx) => {
+                // The volume may also be changed when nothing is playing
+                _ = tx.send(self.spirc.set_volume(volume).map_err(PlayerError::from))
+            }
+            PlayerCommand::Seek(position_ms, tx) => {
+                _ = tx.send(self.control(|spirc| spirc.set_position_ms(position_ms)))
+            }
+            PlayerCommand::SetShuffle(shuffle, tx) => {
+                _ = tx.send(self.control(|spirc| spirc.shuffle(shuffle)))
+            }
+            PlayerCommand::SetRepeat(repeat, tx) => _ = tx.send(self.set_repeat(repeat).await),
+            PlayerCommand::SetSpeed(speed, tx) => _ = tx.send(self.set_speed(speed).await),
+            PlayerCommand::Nudge(tx) => _ = tx.send(self.nudge()),
+            PlayerCommand::TakeOverStall(tx) => {
+                self.stalled = true;
+                _ = tx.send(Ok(()));
+            }
+            PlayerCommand::Load(id, tx) => self.load(id, tx),
+            PlayerCommand::Resume(info, position_ms, tx) => {
+                _ = tx.send(self.resume(*info, position_ms).await)
+            }
 
             PlayerCommand::GetPlaybackInfo(tx) => _ = tx.send(self.playback_info.clone()),
+            PlayerCommand::GetVolume(tx) => _ = tx.send(self.mixer.volume()),
             PlayerCommand::GetLyrics(tx) => self.get_lyrics(tx).await,
 
+            PlayerCommand::ContextResolved(id, tracks, tx) => {
+                let result = tracks.and_then(|tracks| {
+                    self.load_tracks(id, &tracks, true)
+                        .map_err(PlayerError::from)
+                });
+
+                _ = tx.send(result);
+            }
+            PlayerCommand::ReleaseDate(track_id, date) => {
+                self.update_release_date(track_id, date).await
+            }
+            PlayerCommand::PlaybackError { error, low_bitrate } => {
+                self.report_playback_error(error, low_bitrate).await
+            }
+
             PlayerCommand::Shutdown => self.commands.close(),
         };
     }
@@ -236,12 +526,20 @@ impl Player {
         trace!("Spotify event received: {event:#?}");
 
         match event {
-            SpotifyPlayerEvent::PositionCorrection { position_ms, .. }
-            | SpotifyPlayerEvent::Seeked { position_ms, .. } => {
+            SpotifyPlayerEvent::PositionCorrection { position_ms, .. } => {
                 if let Some(playback_info) = self.playback_info.as_mut() {
                     playback_info.update_playback(position_ms, true);
                 }
             }
+            SpotifyPlayerEvent::Seeked { position_ms, .. } => {
+                if let Some(playback_info) = self.playback_info.as_mut() {
+                    // Seeking does not change whether we're playing or not
+                    let playing = playback_info.playing();
+                    playback_info.update_playback(position_ms, playing);
+                }
+
+                _ = self.events.send(PlayerEvent::Seeked(position_ms)).await;
+            }
             SpotifyPlayerEvent::Playing { position_ms, .. } => {
                 _ = self.events.send(PlayerEvent::Play).await;
 
@@ -266,10 +564,28 @@ impl Player {
                 self.playback_info = None;
             }
             SpotifyPlayerEvent::TrackChanged { audio_item } => {
+                self.retrieve_release_date(&audio_item);
+
                 if let Some(playback_info) = self.playback_info.as_mut() {
                     playback_info.update_track(*audio_item);
                 } else {
-                    self.playback_info = Some(PlaybackInfo::new(*audio_item, 0, false));
+                    let mut playback_info =
+                        PlaybackInfo::new(*audio_item, 0, false, self.mixer.volume(), self.quality);
+                    playback_info.update_shuffle(self.shuffle);
+                    playback_info.update_repeat(self.repeat);
+
+                    self.playback_info = Some(playback_info);
+                }
+
+                if let Some(playback_info) = self.playback_info.as_mut() {
+                    playback_info.update_speed(self.speed.factor());
+
+                    // Changing the pitch of someone talking sounds off, so episodes keep theirs
+                    self.speed.set_mode(if playback_info.is_episode() {
+                        SpeedMode::Stretch
+                    } else {
+                        SpeedMode::Resample
+                    });
                 }
 
                 _ = self
@@ -279,15 +595,402 @@ impl Player {
                     )))
                     .await;
             }
+            SpotifyPlayerEvent::VolumeChanged { volume } => {
+                if let Some(playback_info) = self.playback_info.as_mut() {
+                    playback_info.update_volume(volume);
+                }
+
+                _ = self.events.send(PlayerEvent::VolumeChanged(volume)).await;
+            }
+            SpotifyPlayerEvent::ShuffleChanged { shuffle } => {
+                self.shuffle = shuffle;
+
+                if let Some(playback_info) = self.playback_info.as_mut() {
+                    playback_info.update_shuffle(shuffle);
+                }
+
+                _ = self.events.send(PlayerEvent::ShuffleChanged(shuffle)).await;
+            }
+            SpotifyPlayerEvent::RepeatChanged { repeat } => {
+                // Spotify only knows about repeating the context, so keep repeating the track if we're already doing that
+                let repeat = match (repeat, self.repeat) {
+                    (true, RepeatMode::Track) => RepeatMode::Track,
+                    (true, _) => RepeatMode::Context,
+                    (false, _) => RepeatMode::Off,
+                };
+
+                self.update_repeat(repeat).await;
+            }
+            SpotifyPlayerEvent::EndOfTrack { track_id, .. } => {
+                _ = self.events.send(PlayerEvent::EndOfTrack(track_id)).await;
+            }
+            SpotifyPlayerEvent::Loading { track_id, .. } => {
+                // Preloaded tracks were checked before they started downloading
+                if self.preloaded.take() != Some(track_id) {
+                    self.record_cache_usage(track_id);
+                }
+            }
+            SpotifyPlayerEvent::Preloading { track_id } => {
+                self.preloaded = Some(track_id);
+                self.record_cache_usage(track_id);
+
+                _ = self.events.send(PlayerEvent::Preloading(track_id)).await;
+            }
+            SpotifyPlayerEvent::Unavailable { track_id, .. } => {
+                _ = self.events.send(PlayerEvent::Unavailable(track_id)).await;
+
+                self.retrieve_playback_error(track_id);
+            }
+            SpotifyPlayerEvent::SessionClientChanged {
+                client_id,
+                client_name,
+                ..
+            } => {
+                _ = self
+                    .events
+                    .send(PlayerEvent::SessionClientChanged {
+                        client_id,
+                        client_name,
+                    })
+                    .await;
+            }
             _ => {}
         }
     }
 
     async fn handle_sink_event(&self, event: SinkEvent) {
-        if let SinkEvent::Start = event {
-            if let Err(why) = self.track.play() {
-                error!("Failed to resume songbird track: {why}");
+        match event {
+            SinkEvent::Start => {
+                if let Err(why) = self.track.play() {
+                    error!("Failed to resume songbird track: {why}");
+                }
+            }
+            SinkEvent::RepeatTrack => {
+                // Spotify only knows how to repeat the context, so the track is started over before it ends
+                if self.repeat == RepeatMode::Track {
+                    if let Err(why) = self.spirc.set_position_ms(0) {
+                        error!("Failed to repeat track: {why}");
+                    }
+                }
             }
+            SinkEvent::Stop => {}
+        }
+    }
+
+    /// Start playing a track, album, playlist, artist, episode or show on this device.
+    ///
+    /// The tracks within the given item are retrieved in the background, so that the player keeps responding in the
+    /// meantime. They are loaded as the playing context once they arrive.
+    fn load(&self, id: SpotifyId, tx: Reply) {
+        let session = self.session.clone();
+        let inner_tx = self.commands_inner_tx.clone();
+
+        tokio::spawn(async move {
+            let tracks = Self::resolve_context(&session, id).await;
+
+            _ = inner_tx
+                .send(PlayerCommand::ContextResolved(id, tracks, tx))
+                .await;
+        });
+    }
+
+    /// The tracks that are played when loading a track, album, playlist, artist, episode or show
+    async fn resolve_context(
+        session: &SpotifySession,
+        id: SpotifyId,
+    ) -> Result<Vec<SpotifyId>, PlayerError> {
+        let tracks: Vec<SpotifyId> = match id.item_type {
+            SpotifyItemType::Track | SpotifyItemType::Episode => vec![id],
+            SpotifyItemType::Album => Album::get(session, &id).await?.tracks().copied().collect(),
+            SpotifyItemType::Playlist => Playlist::get(session, &id)
+                .await?
+                .tracks()
+                .copied()
+                .collect(),
+            SpotifyItemType::Artist => Artist::get(session, &id)
+                .await?
+                .top_tracks
+                .for_country(&session.country())
+                .to_vec(),
+            SpotifyItemType::Show => Show::get(session, &id).await?.episodes.to_vec(),
+            item_type => {
+                return Err(PlayerError::Rejected(format!(
+                    "Cannot play items of type {item_type:?}"
+                )))
+            }
+        };
+
+        if tracks.is_empty() {
+            return Err(PlayerError::Rejected(format!(
+                "There is nothing to play in {id:?}"
+            )));
+        }
+
+        Ok(tracks)
+    }
+
+    /// Continue playing a track from the given position, for example after the player has been recreated
+    async fn resume(
+        &mut self,
+        playback_info: PlaybackInfo,
+        position_ms: u32,
+    ) -> Result<(), PlayerError> {
+        let track_id = playback_info.track_id();
+
+        self.shuffle = playback_info.shuffle();
+        self.update_repeat(playback_info.repeat()).await;
+
+        self.load_tracks(track_id, &[track_id], playback_info.playing())?;
+        self.spirc.set_position_ms(position_ms)?;
+
+        Ok(())
+    }
+
+    fn load_tracks(
+        &mut self,
+        context: SpotifyId,
+        tracks: &[SpotifyId],
+        start_playing: bool,
+    ) -> Result<(), librespot::core::Error> {
+        let tracks = tracks
+            .iter()
+            .map(|track| {
+                let mut track_ref = TrackRef::new();
+                track_ref.set_gid(track.to_raw().to_vec());
+                track_ref.set_uri(track.to_uri()?);
+
+                Ok(track_ref)
+            })
+            .collect::<Result<Vec<_>, librespot::core::Error>>()?;
+
+        // Commands are ignored by Spotify unless this device is the active one
+        self.spirc.activate()?;
+        self.spirc.load(SpircLoadCommand {
+            context_uri: context.to_uri()?,
+            start_playing,
+            shuffle: self.shuffle,
+            repeat: self.repeat != RepeatMode::Off,
+            playing_track_index: 0,
+            tracks,
+        })?;
+
+        Ok(())
+    }
+
+    /// Run a Spotify Connect command, which only makes sense if something is playing on this device
+    fn control(
+        &self,
+        command: impl FnOnce(&Spirc) -> Result<(), librespot::core::Error>,
+    ) -> Result<(), PlayerError> {
+        if self.playback_info.is_none() {
+            return Err(PlayerError::NoActiveDevice);
+        }
+
+        Ok(command(&self.spirc)?)
+    }
+
+    async fn set_repeat(&mut self, repeat: RepeatMode) -> Result<(), PlayerError> {
+        // Repeating a single track is handled by us, but Spotify still has to repeat the context
+        //  so that playback doesn't stop if the track happens to be the last one
+        self.control(|spirc| spirc.repeat(repeat != RepeatMode::Off))?;
+
+        self.update_repeat(repeat).await;
+
+        Ok(())
+    }
+
+    async fn update_repeat(&mut self, repeat: RepeatMode) {
+        if self.repeat == repeat {
+            return;
+        }
+
+        self.repeat = repeat;
+        self.crossfade.set_repeat(repeat == RepeatMode::Track);
+
+        if let Some(playback_info) = self.playback_info.as_mut() {
+            playback_info.update_repeat(repeat);
+        }
+
+        _ = self.events.send(PlayerEvent::RepeatChanged(repeat)).await;
+    }
+
+    async fn set_speed(&mut self, speed: f32) -> Result<(), PlayerError> {
+        self.speed.set_factor(speed);
+
+        // The speed may have been limited
+        let speed = self.speed.factor();
+
+        if let Some(playback_info) = self.playback_info.as_mut() {
+            playback_info.update_speed(speed);
+        }
+
+        _ = self.events.send(PlayerEvent::SpeedChanged(speed)).await;
+
+        Ok(())
+    }
+
+    /// Report when Spotify is playing, but the stream has been handing out silence for too long
+    async fn check_stall(&mut self) {
+        if self.stall_threshold.is_zero() {
+            return;
+        }
+
+        if !self
+            .playback_info
+            .as_ref()
+            .is_some_and(PlaybackInfo::playing)
+        {
+            // Nothing is supposed to be playing, so there's nothing to recover from
+            self.stalled_for = Duration::ZERO;
+            return;
+        }
+
+        let Some(starved_for) = self
+            .stream
+            .starved_for()
+            .filter(|duration| *duration >= WATCHDOG_INTERVAL)
+        else {
+            self.stalled_for = Duration::ZERO;
+
+            if std::mem::take(&mut self.stalled) {
+                _ = self.events.send(PlayerEvent::Recovered).await;
+            }
+
+            return;
+        };
+
+        self.stalled_for += WATCHDOG_INTERVAL;
+
+        if self.stalled_for >= self.stall_threshold {
+            // Give the recovery a full threshold to work before reporting again
+            self.stalled_for = Duration::ZERO;
+            self.stalled = true;
+
+            _ = self.events.send(PlayerEvent::Stalled(starved_for)).await;
+        }
+    }
+
+    fn nudge(&self) -> Result<(), PlayerError> {
+        let Some(playback_info) = &self.playback_info else {
+            return Err(PlayerError::NoActiveDevice);
+        };
+
+        Ok(self
+            .spirc
+            .set_position_ms(playback_info.current_position())?)
+    }
+
+    /// Keep track of whether a track is played from the cache, without holding up the player
+    fn record_cache_usage(&self, track_id: SpotifyId) {
+        let Some(cache) = self.cache.clone() else {
+            return;
+        };
+
+        let session = self.session.clone();
+
+        tokio::spawn(async move { cache.record(&session, track_id).await });
+    }
+
+    /// Retrieve the release date of a track's album, or the publish date of an episode.
+    ///
+    /// This is not part of the audio item, so the full metadata has to be retrieved. That happens in the background,
+    /// so the track change doesn't have to wait for it.
+    fn retrieve_release_date(&self, audio_item: &AudioItem) {
+        let session = self.session.clone();
+        let inner_tx = self.commands_inner_tx.clone();
+        let track_id = audio_item.track_id;
+        let is_episode = matches!(audio_item.unique_fields, UniqueFields::Episode { .. });
+
+        tokio::spawn(async move {
+            let result = if is_episode {
+                Episode::get(&session, &track_id)
+                    .await
+                    .map(|episode| episode.publish_time)
+            } else {
+                Track::get(&session, &track_id)
+                    .await
+                    .map(|track| track.album.date)
+            };
+
+            match result {
+                Ok(date) => {
+                    _ = inner_tx
+                        .send(PlayerCommand::ReleaseDate(track_id, date))
+                        .await
+                }
+                Err(why) => warn!("Failed to retrieve release date: {why}"),
+            }
+        });
+    }
+
+    async fn update_release_date(&mut self, track_id: SpotifyId, date: Date) {
+        // The track might have changed while the date was being retrieved
+        let Some(playback_info) = self
+            .playback_info
+            .as_mut()
+            .filter(|playback_info| playback_info.track_id() == track_id)
+        else {
+            return;
+        };
+
+        playback_info.update_release_date(Some(date));
+
+        _ = self.events.send(PlayerEvent::ReleaseDateRetrieved).await;
+    }
+
+    /// Figure out why a track could not be played.
+    ///
+    /// Librespot only tells us that a track is unavailable, so the metadata has to be checked again. That happens in
+    /// the background, as Spotify will already be moving on to the next track.
+    fn retrieve_playback_error(&self, track_id: SpotifyId) {
+        let session = self.session.clone();
+        let inner_tx = self.commands_inner_tx.clone();
+
+        tokio::spawn(async move {
+            let mut low_bitrate = false;
+
+            let (name, reason) = match AudioItem::get_file(&session, track_id).await {
+                Ok(item) if item.availability.is_err() => {
+                    (Some(item.name), PlaybackErrorReason::Restricted)
+                }
+                Ok(item) => {
+                    // Spotify claims the track can be played, so the audio itself failed to load
+                    low_bitrate = item.files.contains_key(&AudioFileFormat::OGG_VORBIS_96);
+
+                    (Some(item.name), PlaybackErrorReason::AudioUnavailable)
+                }
+                Err(why) if why.kind == ErrorKind::NotFound => {
+                    (None, PlaybackErrorReason::NotFound)
+                }
+                Err(why) => {
+                    error!("Failed to retrieve metadata of unavailable track: {why}");
+
+                    (None, PlaybackErrorReason::AudioUnavailable)
+                }
+            };
+
+            let error = PlaybackError {
+                track_id,
+                name,
+                reason,
+            };
+
+            _ = inner_tx
+                .send(PlayerCommand::PlaybackError { error, low_bitrate })
+                .await;
+        });
+    }
+
+    async fn report_playback_error(&self, error: PlaybackError, low_bitrate: bool) {
+        // 96kbps often causes audio key errors, which is the only failure that a higher bitrate can fix. Librespot
+        //  cannot change the bitrate of a running player, so the session has to recreate it.
+        if self.quality == Quality::Low && low_bitrate {
+            _ = self
+                .events
+                .send(PlayerEvent::QualityUnavailable(error))
+                .await;
+        } else {
+            _ = self.events.send(PlayerEvent::PlaybackError(error)).await;
         }
     }
 
@@ -322,12 +1025,18 @@ impl Drop for Player {
     fn drop(&mut self) {
         _ = self.spirc.shutdown();
         _ = self.stream.flush();
+
+        // The sink might be waiting for room in a buffer that nobody reads from anymore
+        self.stream.close();
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct PlayerHandle {
     commands: mpsc::Sender<PlayerCommand>,
+    device_id: String,
+    stream: Stream,
+    filters: Filters,
 }
 
 impl PlayerHandle {
@@ -335,20 +1044,102 @@ impl PlayerHandle {
         !self.commands.is_closed()
     }
 
-    pub async fn next_track(&self) {
-        _ = self.commands.send(PlayerCommand::NextTrack).await;
+    /// The Spotify Connect device ID of this player
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// How well audio has been flowing from Spotify to Discord
+    pub fn stream_stats(&self) -> StreamStats {
+        self.stream.stats()
+    }
+
+    /// Replace the filters that the audio runs through, which takes effect without interrupting playback
+    pub fn set_filters(&self, chain: FilterChain) {
+        self.filters.set(chain);
     }
 
-    pub async fn previous_track(&self) {
-        _ = self.commands.send(PlayerCommand::PreviousTrack).await;
+    pub async fn next_track(&self) -> Result<(), PlayerError> {
+        self.control(PlayerCommand::NextTrack).await
     }
 
-    pub async fn pause(&self) {
-        _ = self.commands.send(PlayerCommand::Pause).await;
+    pub async fn previous_track(&self) -> Result<(), PlayerError> {
+        self.control(PlayerCommand::PreviousTrack).await
     }
 
-    pub async fn play(&self) {
-        _ = self.commands.send(PlayerCommand::Play).await;
+    pub async fn pause(&self) -> Result<(), PlayerError> {
+        self.control(PlayerCommand::Pause).await
+    }
+
+    pub async fn play(&self) -> Result<(), PlayerError> {
+        self.control(PlayerCommand::Play).await
+    }
+
+    /// Set the playback volume, where `u16::MAX` is the loudest possible volume.
+    ///
+    /// This goes through Spotify, so the new volume will also show up in the Spotify app.
+    pub async fn set_volume(&self, volume: u16) -> Result<(), PlayerError> {
+        self.control(|tx| PlayerCommand::SetVolume(volume, tx))
+            .await
+    }
+
+    /// Jump to a specific position (in milliseconds) in the current track
+    pub async fn seek(&self, position_ms: u32) -> Result<(), PlayerError> {
+        self.control(|tx| PlayerCommand::Seek(position_ms, tx))
+            .await
+    }
+
+    /// Play audio faster or slower, where `1.0` is normal speed.
+    ///
+    /// Music changes in pitch along with the speed, while episodes keep their pitch.
+    pub async fn set_speed(&self, speed: f32) -> Result<(), PlayerError> {
+        self.control(|tx| PlayerCommand::SetSpeed(speed, tx)).await
+    }
+
+    /// Try to get stalled audio going again, without interrupting playback
+    pub async fn nudge(&self) -> Result<(), PlayerError> {
+        self.control(PlayerCommand::Nudge).await
+    }
+
+    /// Continue the stall of the player that this one replaced, so that it reports when audio flows again
+    pub async fn take_over_stall(&self) -> Result<(), PlayerError> {
+        self.control(PlayerCommand::TakeOverStall).await
+    }
+
+    pub async fn set_shuffle(&self, shuffle: bool) -> Result<(), PlayerError> {
+        self.control(|tx| PlayerCommand::SetShuffle(shuffle, tx))
+            .await
+    }
+
+    pub async fn set_repeat(&self, repeat: RepeatMode) -> Result<(), PlayerError> {
+        self.control(|tx| PlayerCommand::SetRepeat(repeat, tx))
+            .await
+    }
+
+    /// Load a track, album, playlist, artist, episode or show and start playing it
+    pub async fn load(&self, id: SpotifyId) -> Result<(), PlayerError> {
+        self.control_within(LOAD_TIMEOUT, |tx| PlayerCommand::Load(id, tx))
+            .await
+    }
+
+    /// Load the track of the given playback info and continue playing it from `position_ms`
+    pub async fn resume(
+        &self,
+        playback_info: PlaybackInfo,
+        position_ms: u32,
+    ) -> Result<(), PlayerError> {
+        self.control_within(LOAD_TIMEOUT, |tx| {
+            PlayerCommand::Resume(Box::new(playback_info), position_ms, tx)
+        })
+        .await
+    }
+
+    /// Retrieve the current playback volume from the mixer
+    pub async fn volume(&self) -> Result<u16> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(PlayerCommand::GetVolume(tx)).await?;
+
+        Ok(rx.await?)
     }
 
     pub async fn playback_info(&self) -> Result<Option<PlaybackInfo>> {
@@ -370,4 +1161,31 @@ impl PlayerHandle {
     pub async fn shutdown(&self) {
         _ = self.commands.send(PlayerCommand::Shutdown).await;
     }
+
+    /// Send a command to the player and wait until it has been carried out
+    async fn control(
+        &self,
+        command: impl FnOnce(Reply) -> PlayerCommand,
+    ) -> Result<(), PlayerError> {
+        self.control_within(COMMAND_TIMEOUT, command).await
+    }
+
+    /// Send a command to the player and wait until it has been carried out, for at most `timeout`
+    async fn control_within(
+        &self,
+        timeout: Duration,
+        command: impl FnOnce(Reply) -> PlayerCommand,
+    ) -> Result<(), PlayerError> {
+        let (tx, rx) = oneshot::channel();
+        self.commands
+            .send(command(tx))
+            .await
+            .map_err(|_| PlayerError::Shutdown)?;
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(PlayerError::Shutdown),
+            Err(_) => Err(PlayerError::TimedOut),
+        }
+    }
 }
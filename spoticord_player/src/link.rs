@@ -0,0 +1,160 @@
+use librespot::core::{spotify_id::SpotifyItemType, SpotifyId};
+
+/// Parse a Spotify link into a [`SpotifyId`].
+///
+/// This accepts both `open.spotify.com` URLs (including localized `/intl-xx/` ones) and `spotify:` URIs,
+/// as long as they point to a track, album, playlist, artist, episode or show.
+pub fn parse(input: &str) -> Option<SpotifyId> {
+    let input = input.trim();
+
+    let (item_type, id) = if let Some(uri) = input.strip_prefix("spotify:") {
+        // URIs may contain extra segments, like `spotify:user:<user>:playlist:<id>`
+        let mut parts = uri.rsplit(':');
+        let id = parts.next()?;
+        let item_type = parts.next()?;
+
+        (item_type, id)
+    } else {
+        let url = input
+            .strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+            .unwrap_or(input);
+
+        // Get rid of tracking parameters like `?si=...`
+        let url = url.split(['?', '#']).next()?;
+        let path = url.strip_prefix("open.spotify.com/")?;
+
+        let mut parts = path.split('/').filter(|part| !part.is_empty());
+        let mut item_type = parts.next()?;
+
+        // Localized links look like `/intl-de/track/<id>`
+        if item_type.starts_with("intl-") {
+            item_type = parts.next()?;
+        }
+
+        let id = parts.next()?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        (item_type, id)
+    };
+
+    let item_type = match item_type {
+        "track" => SpotifyItemType::Track,
+        "album" => SpotifyItemType::Album,
+        "playlist" => SpotifyItemType::Playlist,
+        "artist" => SpotifyItemType::Artist,
+        "episode" => SpotifyItemType::Episode,
+        "show" => SpotifyItemType::Show,
+        _ => return None,
+    };
+
+    let mut spotify_id = SpotifyId::from_base62(id).ok()?;
+    spotify_id.item_type = item_type;
+
+    Some(spotify_id)
+}
+
+/// Parse a Spotify link into a [`SpotifyId`], but only if it points to a track
+pub fn parse_track(input: &str) -> Option<SpotifyId> {
+    parse(input).filter(|id| id.item_type == SpotifyItemType::Track)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4uLU6hMCjMI75M1A2tKUQC";
+
+    fn id(item_type: SpotifyItemType) -> Option<SpotifyId> {
+        let mut id = SpotifyId::from_base62(ID).unwrap();
+        id.item_type = item_type;
+
+        Some(id)
+    }
+
+    #[test]
+    fn parses_urls() {
+        assert_eq!(
+            parse(&format!("https://open.spotify.com/track/{ID}")),
+            id(SpotifyItemType::Track)
+        );
+        assert_eq!(
+            parse(&format!("http://open.spotify.com/album/{ID}")),
+            id(SpotifyItemType::Album)
+        );
+        assert_eq!(
+            parse(&format!("open.spotify.com/show/{ID}/")),
+            id(SpotifyItemType::Show)
+        );
+    }
+
+    #[test]
+    fn parses_localized_urls() {
+        assert_eq!(
+            parse(&format!("https://open.spotify.com/intl-de/track/{ID}")),
+            id(SpotifyItemType::Track)
+        );
+        assert_eq!(
+            parse(&format!("https://open.spotify.com/intl-pt/episode/{ID}")),
+            id(SpotifyItemType::Episode)
+        );
+    }
+
+    #[test]
+    fn ignores_query_strings() {
+        assert_eq!(
+            parse(&format!("https://open.spotify.com/playlist/{ID}?si=abc123")),
+            id(SpotifyItemType::Playlist)
+        );
+        assert_eq!(
+            parse(&format!(
+                "https://open.spotify.com/artist/{ID}?si=abc&nd=1#top"
+            )),
+            id(SpotifyItemType::Artist)
+        );
+    }
+
+    #[test]
+    fn parses_uris() {
+        assert_eq!(
+            parse(&format!("spotify:track:{ID}")),
+            id(SpotifyItemType::Track)
+        );
+        assert_eq!(
+            parse(&format!("  spotify:episode:{ID} ")),
+            id(SpotifyItemType::Episode)
+        );
+    }
+
+    #[test]
+    fn parses_user_playlist_uris() {
+        assert_eq!(
+            parse(&format!("spotify:user:spotify:playlist:{ID}")),
+            id(SpotifyItemType::Playlist)
+        );
+    }
+
+    #[test]
+    fn rejects_other_links() {
+        assert_eq!(parse(&format!("https://example.com/track/{ID}")), None);
+        assert_eq!(parse(&format!("https://open.spotify.com/user/{ID}")), None);
+        assert_eq!(
+            parse(&format!("https://open.spotify.com/track/{ID}/extra")),
+            None
+        );
+        assert_eq!(parse("spotify:track:not-an-id"), None);
+        assert_eq!(parse("not a link"), None);
+    }
+
+    #[test]
+    fn parse_track_only_accepts_tracks() {
+        assert_eq!(
+            parse_track(&format!("spotify:track:{ID}")),
+            id(SpotifyItemType::Track)
+        );
+        assert_eq!(parse_track(&format!("spotify:album:{ID}")), None);
+    }
+}
@@ -25,6 +25,12 @@ pub enum Error {
 
     #[error(transparent)]
     Librespot(#[from] librespot::core::Error),
+
+    #[error(transparent)]
+    Spotify(#[from] rspotify::ClientError),
+
+    #[error(transparent)]
+    SpotifyId(#[from] rspotify::model::IdError),
 }
 
 pub type Result<T> = ::core::result::Result<T, Error>;
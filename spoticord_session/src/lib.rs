@@ -2,6 +2,8 @@ pub mod error;
 pub mod lyrics_embed;
 pub mod manager;
 pub mod playback_embed;
+pub mod queue_embed;
+pub mod spotify;
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use error::Error;
@@ -11,10 +13,11 @@ use librespot::{
     discovery::Credentials,
     protocol::{authentication::AuthenticationType, keyexchange::ErrorCode},
 };
-use log::{debug, error, trace};
-use lyrics_embed::LyricsEmbed;
+use log::{debug, error, info, trace, warn};
+use lyrics_embed::{LyricsEmbed, LyricsEmbedHandle};
 use manager::{SessionManager, SessionQuery};
 use playback_embed::{PlaybackEmbed, PlaybackEmbedHandle};
+use queue_embed::{QueueEmbed, QueueEmbedHandle};
 use serenity::{
     all::{
         ChannelId, CommandInteraction, CreateEmbed, CreateMessage, GuildChannel, GuildId, UserId,
@@ -22,19 +25,47 @@ use serenity::{
     async_trait,
 };
 use songbird::{model::payload::ClientDisconnect, Call, CoreEvent, Event, EventContext};
-use spoticord_player::{Player, PlayerEvent, PlayerHandle};
-use spoticord_utils::discord::Colors;
-use std::{ops::ControlFlow, sync::Arc, time::Duration};
+use spoticord_database::{error::DatabaseResultExt, Database, EqualizerPreset, NormalisationMode};
+use spoticord_player::{
+    equalizer::{Preset, BANDS},
+    error::{PlaybackError, PlayerError},
+    info::{PlaybackInfo, Quality},
+    BufferBounds, Normalisation, Player, PlayerEvent, PlayerHandle, PlayerSettings,
+};
+use spoticord_utils::discord::{escape, Colors};
+use std::{collections::HashMap, ops::ControlFlow, sync::Arc, time::Duration};
 use tokio::{
     sync::{mpsc, oneshot, Mutex},
-    task::JoinHandle,
+    time::Instant,
 };
 
+/// The minimum time between two messages about tracks that could not be played
+const PLAYBACK_ERROR_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// After this many tracks in a row could not be played, the bot skips ahead by itself
+const PLAYBACK_ERROR_SKIP_THRESHOLD: u32 = 3;
+
+/// How often the bot tries to reconnect to Spotify after the connection has been lost
+const RECONNECT_ATTEMPTS: u32 = 5;
+
+/// The delay before the first reconnect attempt, which doubles after every failed attempt
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Spotify plays related tracks from a station once the context of the user has ended
+const AUTOPLAY_CONTEXT_PREFIX: &str = "spotify:station:";
+
+/// How often the player is recreated in an attempt to recover from stalled audio, before giving up
+const STALL_RESTART_ATTEMPTS: u32 = 2;
+
 #[derive(Debug)]
 pub enum SessionCommand {
     GetOwner(oneshot::Sender<UserId>),
     GetPlayer(oneshot::Sender<PlayerHandle>),
     GetActive(oneshot::Sender<bool>),
+    GetRequester(oneshot::Sender<Option<UserId>>),
+    GetAutoplayed(oneshot::Sender<bool>),
+    GetEqualizer(oneshot::Sender<Preset>),
+    GetSpeed(oneshot::Sender<f32>),
 
     CreatePlaybackEmbed(
         SessionHandle,
@@ -42,8 +73,19 @@ pub enum SessionCommand {
         playback_embed::UpdateBehavior,
     ),
     CreateLyricsEmbed(SessionHandle, CommandInteraction),
+    CreateQueueEmbed(SessionHandle, CommandInteraction),
 
     Reactivate(UserId, oneshot::Sender<Result<()>>),
+    TransferPlayback(oneshot::Sender<Result<bool>>),
+    AddToQueue(String, UserId, oneshot::Sender<Result<()>>),
+    TrackRequested(String, UserId),
+    TrackAutoplayed(String),
+    SetEqualizer(Preset),
+    SetSpeed(f32, oneshot::Sender<std::result::Result<(), PlayerError>>),
+    Reconnect(u32),
+    HostLeft,
+    HostReturned(UserId),
+    StandbyExpired,
     ShutdownPlayer,
     Disconnect,
     DisconnectTimedOut,
@@ -58,9 +100,44 @@ pub struct Session {
     call: Arc<Mutex<Call>>,
     player: PlayerHandle,
 
+    /// Reusable credentials of the owner, used to recreate the player
+    credentials: Credentials,
+    settings: PlayerSettings,
+
     owner: UserId,
     active: bool,
 
+    /// Tracks that were added to the queue using `/add`, and who added them
+    requests: HashMap<String, UserId>,
+
+    /// The user who requested the track that is currently playing
+    requester: Option<UserId>,
+
+    /// Whether the track that is currently playing was picked by autoplay
+    autoplayed: bool,
+
+    /// The amount of tracks in a row that could not be played
+    playback_errors: u32,
+    last_playback_error_message: Option<Instant>,
+
+    /// The amount of playback errors that happened during the cooldown
+    suppressed_playback_errors: u32,
+
+    /// Set while the player is being recreated after losing connection to Spotify
+    reconnecting: bool,
+
+    /// What was playing when the connection was lost, and at which position
+    resume: Option<(PlaybackInfo, u32)>,
+
+    /// The amount of attempts that have been made to recover from the current audio stall
+    stall_recoveries: u32,
+
+    /// Set while the host is out of the call, cancels shutting down the player when they return
+    standby_tx: Option<oneshot::Sender<()>>,
+
+    /// Whether music was playing when the host left, and should continue once they return
+    standby_playing: bool,
+
     timeout_tx: Option<oneshot::Sender<()>>,
 
     commands: mpsc::Receiver<SessionCommand>,
@@ -70,7 +147,8 @@ pub struct Session {
     commands_inner_rx: mpsc::Receiver<SessionCommand>,
 
     playback_embed: Option<PlaybackEmbedHandle>,
-    lyrics_embed: Option<JoinHandle<()>>,
+    lyrics_embed: Option<LyricsEmbedHandle>,
+    queue_embed: Option<QueueEmbedHandle>,
 }
 
 impl Session {
@@ -119,6 +197,9 @@ impl Session {
             .await?
             .device_name;
 
+        let settings = player_settings(&session_manager.database(), guild_id).await?;
+        let username = account.username.clone();
+
         let credentials = match account
             .session_token
             .and_then(|val| BASE64.decode(&val).ok())
@@ -161,50 +242,64 @@ impl Session {
             call.add_global_event(Event::Core(CoreEvent::ClientDisconnect), handle.clone());
         }
 
-        let (player, events, auth_data) =
-            match Player::create(credentials, call.clone(), device_name).await {
-                Ok(player) => player,
-                Err(why) => {
-                    // Leave call on error, otherwise bot will be stuck in call forever until manually disconnected or taken over
-                    _ = call.lock().await.leave().await;
-
-                    error!("Failed to create player: {why}");
-
-                    if let Some(connection::AuthenticationError::LoginFailed(
-                        ErrorCode::BadCredentials,
-                    )) = why.error.downcast_ref::<connection::AuthenticationError>()
-                    {
-                        // Authentication failed, clear tokens in database (depending on which type of auth failed)
-
-                        if credentials_cached {
-                            session_manager
-                                .database()
-                                .update_session_token(owner.to_string(), None)
-                                .await
-                                .ok();
-                        } else {
-                            session_manager
-                                .database()
-                                .delete_account(owner.to_string())
-                                .await
-                                .ok();
-                        }
-
-                        return Err(AuthenticationFailed);
+        let cache = session_manager.audio_cache();
+        let (player, events, auth_data) = match Player::create(
+            credentials,
+            call.clone(),
+            device_name,
+            settings.clone(),
+            cache,
+        )
+        .await
+        {
+            Ok(player) => player,
+            Err(why) => {
+                // Leave call on error, otherwise bot will be stuck in call forever until manually disconnected or taken over
+                _ = call.lock().await.leave().await;
+
+                error!("Failed to create player: {why}");
+
+                if let Some(connection::AuthenticationError::LoginFailed(
+                    ErrorCode::BadCredentials,
+                )) = why.error.downcast_ref::<connection::AuthenticationError>()
+                {
+                    // Authentication failed, clear tokens in database (depending on which type of auth failed)
+
+                    if credentials_cached {
+                        session_manager
+                            .database()
+                            .update_session_token(owner.to_string(), None)
+                            .await
+                            .ok();
+                    } else {
+                        session_manager
+                            .database()
+                            .delete_account(owner.to_string())
+                            .await
+                            .ok();
                     }
 
-                    return Err(why.into());
+                    return Err(AuthenticationFailed);
                 }
-            };
+
+                return Err(why.into());
+            }
+        };
 
         // Store reusable credentials in DB
         // We don't care if this fails, we'll just fall back on token login
         session_manager
             .database()
-            .update_session_token(owner.to_string(), Some(BASE64.encode(auth_data)))
+            .update_session_token(owner.to_string(), Some(BASE64.encode(&auth_data)))
             .await
             .ok();
 
+        let credentials = Credentials {
+            username: Some(username),
+            auth_type: AuthenticationType::AUTHENTICATION_STORED_SPOTIFY_CREDENTIALS,
+            auth_data,
+        };
+
         let mut session = Self {
             session_manager,
 
@@ -214,10 +309,24 @@ impl Session {
             call,
             player,
 
+            credentials,
+            settings,
+
             guild_id,
             owner,
 
             active: true,
+            requests: HashMap::new(),
+            requester: None,
+            autoplayed: false,
+            playback_errors: 0,
+            last_playback_error_message: None,
+            suppressed_playback_errors: 0,
+            reconnecting: false,
+            resume: None,
+            stall_recoveries: 0,
+            standby_tx: None,
+            standby_playing: false,
             timeout_tx: None,
 
             commands: rx,
@@ -228,6 +337,7 @@ impl Session {
 
             playback_embed: None,
             lyrics_embed: None,
+            queue_embed: None,
         };
         session.start_timeout();
 
@@ -251,7 +361,8 @@ impl Session {
                     }
                 },
 
-                opt_event = self.events.recv(), if self.active => {
+                // The events of a player that lost connection are meaningless
+                opt_event = self.events.recv(), if self.active && !self.reconnecting => {
                     trace!("Received event: {opt_event:#?}");
 
                     let Some(event) = opt_event else {
@@ -285,6 +396,10 @@ impl Session {
             SessionCommand::GetOwner(sender) => _ = sender.send(self.owner),
             SessionCommand::GetPlayer(sender) => _ = sender.send(self.player.clone()),
             SessionCommand::GetActive(sender) => _ = sender.send(self.active),
+            SessionCommand::GetRequester(sender) => _ = sender.send(self.requester),
+            SessionCommand::GetAutoplayed(sender) => _ = sender.send(self.autoplayed),
+            SessionCommand::GetEqualizer(sender) => _ = sender.send(self.settings.equalizer),
+            SessionCommand::GetSpeed(sender) => _ = sender.send(self.settings.speed),
 
             SessionCommand::CreatePlaybackEmbed(handle, interaction, behavior) => {
                 match PlaybackEmbed::create(self, handle, interaction, behavior).await {
@@ -311,10 +426,95 @@ impl Session {
                     }
                 }
             }
+            SessionCommand::CreateQueueEmbed(handle, interaction) => {
+                match QueueEmbed::create(self, handle, interaction).await {
+                    Ok(Some(queue_embed)) => {
+                        if let Some(current) = self.queue_embed.take() {
+                            current.abort();
+                        }
+
+                        self.queue_embed = Some(queue_embed);
+                    }
+                    Ok(None) => {}
+                    Err(why) => {
+                        error!("Failed to create queue embed: {why}");
+                    }
+                }
+            }
 
             SessionCommand::Reactivate(new_owner, tx) => {
                 _ = tx.send(self.reactivate(new_owner).await)
             }
+            SessionCommand::TransferPlayback(tx) => {
+                let database = self.session_manager.database();
+                let owner = self.owner;
+                let device_id = self.player.device_id().to_string();
+
+                // Talking to the Web API might take a while, don't hold up the session in the meantime
+                tokio::spawn(async move {
+                    _ = tx.send(
+                        spotify::transfer_playback(&database, owner.to_string(), &device_id).await,
+                    );
+                });
+            }
+            SessionCommand::AddToQueue(track_id, requester, tx) => {
+                let database = self.session_manager.database();
+                let owner = self.owner;
+                let device_id = self.player.device_id().to_string();
+                let inner_tx = self.commands_inner_tx.clone();
+
+                tokio::spawn(async move {
+                    let result =
+                        spotify::add_to_queue(&database, owner.to_string(), &track_id, &device_id)
+                            .await;
+
+                    if result.is_ok() {
+                        _ = inner_tx
+                            .send(SessionCommand::TrackRequested(track_id, requester))
+                            .await;
+                    }
+
+                    _ = tx.send(result);
+                });
+            }
+            SessionCommand::TrackRequested(track_id, requester) => {
+                self.requests.insert(track_id, requester);
+            }
+            SessionCommand::SetEqualizer(preset) => {
+                // Kept in the settings, so that the equalizer survives the player being recreated
+                self.settings.equalizer = preset;
+                self.player.set_filters(self.settings.filter_chain());
+
+                self.update_playback_embed(true).await;
+            }
+            SessionCommand::SetSpeed(speed, tx) => {
+                let result = self.player.set_speed(speed).await;
+
+                if result.is_ok() {
+                    self.settings.speed = speed;
+                }
+
+                _ = tx.send(result);
+            }
+            SessionCommand::TrackAutoplayed(track_id) => {
+                // The track might have changed again in the meantime
+                if let Ok(Some(info)) = self.player.playback_info().await {
+                    if info.track_id_string().as_ref() == Some(&track_id) {
+                        self.autoplayed = true;
+                        self.update_playback_embed(true).await;
+                    }
+                }
+            }
+            SessionCommand::Reconnect(attempt) => self.reconnect(attempt).await,
+            SessionCommand::HostLeft => self.host_left().await,
+            SessionCommand::HostReturned(user_id) => self.host_returned(user_id).await,
+            SessionCommand::StandbyExpired => {
+                if self.standby_tx.is_some() {
+                    debug!("Host did not return in time, stopping playback");
+
+                    self.shutdown_player().await;
+                }
+            }
             SessionCommand::ShutdownPlayer => self.shutdown_player().await,
             SessionCommand::Disconnect => {
                 self.disconnect().await;
@@ -346,30 +546,333 @@ impl Session {
 
     async fn handle_event(&mut self, event: PlayerEvent) {
         match event {
-            PlayerEvent::Play => self.stop_timeout(),
-            PlayerEvent::Pause => self.start_timeout(),
-            PlayerEvent::Stopped => self.shutdown_player().await,
-            PlayerEvent::TrackChanged(_) => {}
+            PlayerEvent::Play => {
+                self.playback_errors = 0;
+                self.stop_timeout();
+                self.update_playback_embed(true).await;
+            }
+            PlayerEvent::Pause => {
+                self.start_timeout();
+                self.update_playback_embed(true).await;
+            }
+            PlayerEvent::Stopped => {
+                self.shutdown_player().await;
+                self.update_playback_embed(true).await;
+            }
+            PlayerEvent::TrackChanged(info) => {
+                self.requester = info
+                    .track_id_string()
+                    .and_then(|id| self.requests.remove(&id));
+                self.autoplayed = false;
+
+                if self.settings.autoplay && self.requester.is_none() {
+                    self.check_autoplayed(&info);
+                }
+
+                if let Some(queue_embed) = &self.queue_embed {
+                    if queue_embed.invoke_update().await.is_err() {
+                        self.queue_embed = None;
+                    }
+                }
+
+                // Pinned embeds should move down to the latest message when the track changes
+                self.update_playback_embed(false).await;
+            }
+            PlayerEvent::VolumeChanged(_)
+            | PlayerEvent::ShuffleChanged(_)
+            | PlayerEvent::RepeatChanged(_)
+            | PlayerEvent::ReleaseDateRetrieved => {
+                self.update_playback_embed(true).await;
+            }
+            PlayerEvent::Seeked(_) | PlayerEvent::SpeedChanged(_) => {
+                // Make sure synced lyrics jump along with the seek, or keep up with the new speed
+                if let Some(lyrics_embed) = &self.lyrics_embed {
+                    if lyrics_embed.invoke_update().await.is_err() {
+                        self.lyrics_embed = None;
+                    }
+                }
+
+                self.update_playback_embed(true).await;
+            }
+            PlayerEvent::EndOfTrack(track_id) => {
+                trace!("Track {track_id:?} has ended");
+            }
+            PlayerEvent::Preloading(track_id) => {
+                trace!("Preloading track {track_id:?}");
+            }
+            PlayerEvent::Unavailable(track_id) => {
+                debug!("Track {track_id:?} is unavailable");
+            }
+            PlayerEvent::PlaybackError(error) => self.handle_playback_error(error).await,
+            PlayerEvent::QualityUnavailable(error) => {
+                warn!(
+                    "Track {:?} in guild {} failed to load at 96kbps, which is likely an audio key error, restarting the player at 160kbps",
+                    error.track_id, self.guild_id
+                );
+
+                let settings = PlayerSettings {
+                    quality: Quality::Normal,
+                    ..self.settings.clone()
+                };
+
+                // Librespot only takes the bitrate when the player is created. Pick the failed track up from the start
+                //  in case the new device doesn't receive the playback from Spotify.
+                let resume = match self.player.playback_info().await {
+                    Ok(Some(info)) => Some((info, 0)),
+                    _ => None,
+                };
+
+                if let Err(why) = self.restart_player(settings, resume).await {
+                    error!("Failed to restart player at a higher bitrate: {why}");
+
+                    self.shutdown_player().await;
+                    self.update_playback_embed(true).await;
+                }
+            }
+            PlayerEvent::SessionClientChanged {
+                client_id,
+                client_name,
+            } => {
+                debug!("Spotify client {client_name} ({client_id}) has taken control");
+            }
             PlayerEvent::ConnectionReset => {
-                self.disconnect().await;
+                warn!(
+                    "Lost connection to Spotify in guild {}, reconnecting",
+                    self.guild_id
+                );
 
-                _ = self
-                    .text_channel
-                    .send_message(
-                        &self.context,
-                        CreateMessage::new().embed(
-                            CreateEmbed::new()
-                                .title("Spotify connection lost")
-                                .description("The bot has lost connection to the Spotify AP servers.\nThis is most likely caused by a connection reset on Spotify's end.\n\nUse `/join` to resummon the bot to your voice channel.")
-                                .color(Colors::Error),
-                        ),
-                    )
-                    .await;
+                // Remember where we were, as the position would keep moving otherwise
+                self.resume = match self.player.playback_info().await {
+                    Ok(Some(info)) => {
+                        let position = info.current_position();
+
+                        Some((info, position))
+                    }
+                    _ => None,
+                };
+                self.reconnecting = true;
+
+                self.schedule_reconnect(0);
+            }
+            PlayerEvent::Stalled(duration) => self.recover_stall(duration).await,
+            PlayerEvent::Recovered => {
+                info!(
+                    "Audio recovered in guild {} (owner {}) after {} attempt(s)",
+                    self.guild_id,
+                    self.owner,
+                    std::mem::take(&mut self.stall_recoveries)
+                );
+            }
+        }
+    }
+
+    /// Find out whether a track was picked by autoplay, which is only known to the Web API
+    fn check_autoplayed(&self, info: &PlaybackInfo) {
+        let Some(track_id) = info.track_id_string() else {
+            return;
+        };
+
+        let database = self.session_manager.database();
+        let owner = self.owner;
+        let inner_tx = self.commands_inner_tx.clone();
+
+        tokio::spawn(async move {
+            match spotify::playback_context(&database, owner.to_string()).await {
+                Ok(Some(context)) if context.starts_with(AUTOPLAY_CONTEXT_PREFIX) => {
+                    _ = inner_tx
+                        .send(SessionCommand::TrackAutoplayed(track_id))
+                        .await;
+                }
+                Ok(_) => {}
+                Err(why) => error!("Failed to retrieve playback context: {why}"),
+            }
+        });
+    }
+
+    /// Get stalled audio going again, first by nudging the player and then by recreating it
+    async fn recover_stall(&mut self, duration: Duration) {
+        let attempt = self.stall_recoveries;
+        self.stall_recoveries += 1;
+
+        debug!(
+            "Audio stream of guild {} stalled: {}",
+            self.guild_id,
+            self.player.stream_stats()
+        );
+
+        if attempt == 0 {
+            warn!(
+                "Audio stalled for {duration:?} in guild {} (owner {}), nudging playback",
+                self.guild_id, self.owner
+            );
+
+            if let Err(why) = self.player.nudge().await {
+                error!("Failed to nudge playback: {why}");
+            }
+
+            return;
+        }
+
+        if attempt > STALL_RESTART_ATTEMPTS {
+            // Only complain once, the player keeps reporting the stall
+            if attempt > STALL_RESTART_ATTEMPTS + 1 {
+                return;
+            }
+
+            error!(
+                "Audio stalled for {duration:?} in guild {} (owner {}), giving up after {attempt} attempts",
+                self.guild_id, self.owner
+            );
+
+            return;
+        }
+
+        warn!(
+            "Audio stalled for {duration:?} in guild {} (owner {}), recreating player (attempt {attempt})",
+            self.guild_id, self.owner
+        );
+
+        let resume = match self.player.playback_info().await {
+            Ok(Some(info)) => {
+                let position = info.current_position();
+
+                Some((info, position))
+            }
+            _ => None,
+        };
+
+        if let Err(why) = self.restart_player(self.settings.clone(), resume).await {
+            error!(
+                "Failed to recreate stalled player in guild {}: {why}",
+                self.guild_id
+            );
+
+            return;
+        }
+
+        // The new player has never stalled itself, so it has to be told to report when audio is back. Otherwise the
+        //  attempts would never be reset, and the next stall would pick up where this one left off.
+        if let Err(why) = self.player.take_over_stall().await {
+            error!("Failed to hand the stall over to the recreated player: {why}");
+        }
+    }
+
+    fn schedule_reconnect(&self, attempt: u32) {
+        let delay = RECONNECT_BASE_DELAY * 2u32.pow(attempt);
+        let inner_tx = self.commands_inner_tx.clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            _ = inner_tx.send(SessionCommand::Reconnect(attempt)).await;
+        });
+    }
+
+    async fn reconnect(&mut self, attempt: u32) {
+        // The host might have left in the meantime
+        if !self.active || !self.reconnecting {
+            return;
+        }
+
+        match self
+            .restart_player(self.settings.clone(), self.resume.clone())
+            .await
+        {
+            Ok(()) => {
+                debug!("Reconnected to Spotify in guild {}", self.guild_id);
+
+                self.reconnecting = false;
+                self.resume = None;
+                self.update_playback_embed(true).await;
+
+                return;
+            }
+            Err(why) => {
+                error!("Reconnect attempt {} failed: {why}", attempt + 1);
+            }
+        }
+
+        if attempt + 1 < RECONNECT_ATTEMPTS {
+            self.schedule_reconnect(attempt + 1);
+            return;
+        }
+
+        self.disconnect().await;
+
+        _ = self
+            .text_channel
+            .send_message(
+                &self.context,
+                CreateMessage::new().embed(
+                    CreateEmbed::new()
+                        .title("Spotify connection lost")
+                        .description("The bot has lost connection to the Spotify AP servers, and was unable to reconnect.\nThis is most likely caused by a connection reset on Spotify's end.\n\nUse `/join` to resummon the bot to your voice channel.")
+                        .color(Colors::Error),
+                ),
+            )
+            .await;
+
+        self.update_playback_embed(true).await;
+    }
+
+    async fn handle_playback_error(&mut self, error: PlaybackError) {
+        self.playback_errors += 1;
+
+        if self.playback_errors >= PLAYBACK_ERROR_SKIP_THRESHOLD {
+            // Spotify tends to get stuck when a lot of tracks in a row are unplayable
+            self.playback_errors = 0;
+
+            if let Err(why) = self.player.next_track().await {
+                error!("Failed to skip past unplayable tracks: {why}");
+            }
+        }
+
+        // Don't spam the channel when an entire playlist is unplayable
+        if self
+            .last_playback_error_message
+            .is_some_and(|instant| instant.elapsed() < PLAYBACK_ERROR_COOLDOWN)
+        {
+            self.suppressed_playback_errors += 1;
+            return;
+        }
+
+        let track = match (&error.name, error.url()) {
+            (Some(name), Some(url)) => format!("[{}]({url})", escape(name)),
+            (Some(name), None) => format!("**{}**", escape(name)),
+            (None, Some(url)) => format!("<{url}>"),
+            (None, None) => "A track".to_string(),
+        };
+
+        let mut description = format!("{track} could not be played, because {}.", error.reason);
+
+        match std::mem::take(&mut self.suppressed_playback_errors) {
+            0 => {}
+            1 => description += "\n\n1 other track could not be played either.",
+            count => {
+                description += &format!("\n\n{count} other tracks could not be played either.")
             }
         }
 
-        let force_edit = !matches!(event, PlayerEvent::TrackChanged(_));
+        _ = self
+            .text_channel
+            .send_message(
+                &self.context,
+                CreateMessage::new().embed(
+                    CreateEmbed::new()
+                        .title("Cannot play track")
+                        .description(description)
+                        .color(Colors::Warning),
+                ),
+            )
+            .await;
+
+        self.last_playback_error_message = Some(Instant::now());
+    }
 
+    /// Let the playback embed know that it should update.
+    ///
+    /// If `force_edit` is false, pinned embeds will be re-sent instead of edited.
+    async fn update_playback_embed(&mut self, force_edit: bool) {
         if let Some(playback_embed) = &self.playback_embed {
             if playback_embed.invoke_update(force_edit).await.is_err() {
                 self.playback_embed = None;
@@ -430,6 +933,9 @@ impl Session {
             .await?
             .device_name;
 
+        let settings = player_settings(&self.session_manager.database(), self.guild_id).await?;
+        let username = account.username.clone();
+
         let credentials = match account
             .session_token
             .and_then(|val| BASE64.decode(val).ok())
@@ -455,8 +961,10 @@ impl Session {
             AuthenticationType::AUTHENTICATION_STORED_SPOTIFY_CREDENTIALS
         );
 
+        let call = self.call.clone();
+        let cache = self.session_manager.audio_cache();
         let (player, player_events, auth_data) =
-            match Player::create(credentials, self.call.clone(), device_name).await {
+            match Player::create(credentials, call, device_name, settings.clone(), cache).await {
                 Ok(player) => player,
                 Err(why) => {
                     if let Some(connection::AuthenticationError::LoginFailed(
@@ -488,24 +996,172 @@ impl Session {
         // We don't care if this fails, we'll just fall back on token login
         self.session_manager
             .database()
-            .update_session_token(user_id, Some(BASE64.encode(auth_data)))
+            .update_session_token(user_id, Some(BASE64.encode(&auth_data)))
             .await
             .ok();
 
+        self.credentials = Credentials {
+            username: Some(username),
+            auth_type: AuthenticationType::AUTHENTICATION_STORED_SPOTIFY_CREDENTIALS,
+            auth_data,
+        };
+        self.settings = settings;
         self.owner = new_owner;
         self.player = player;
         self.events = player_events;
         self.active = true;
 
+        // Requests were made to the queue of the previous host
+        self.requests.clear();
+        self.requester = None;
+        self.autoplayed = false;
+        self.stall_recoveries = 0;
+
+        // A reconnect of the previous player might have been cut short by shutting it down
+        self.reconnecting = false;
+        self.resume = None;
+
+        Ok(())
+    }
+
+    /// Recreate the player with new settings, without leaving the call.
+    ///
+    /// The new player keeps the device ID of the old one, and the playback of the owner is moved back to it.
+    /// If Spotify no longer knows what was playing, playback continues from `resume` instead.
+    async fn restart_player(
+        &mut self,
+        mut settings: PlayerSettings,
+        resume: Option<(PlaybackInfo, u32)>,
+    ) -> Result<()> {
+        settings.device_id = Some(self.player.device_id().to_string());
+
+        let device_name = self
+            .session_manager
+            .database()
+            .get_user(self.owner.to_string())
+            .await?
+            .device_name;
+
+        self.player.shutdown().await;
+
+        let (player, events, _) = Player::create(
+            self.credentials.clone(),
+            self.call.clone(),
+            device_name,
+            settings.clone(),
+            self.session_manager.audio_cache(),
+        )
+        .await?;
+
+        self.player = player;
+        self.events = events;
+        self.settings = settings;
+
+        let transferred = spotify::transfer_playback(
+            &self.session_manager.database(),
+            self.owner.to_string(),
+            self.player.device_id(),
+        )
+        .await
+        .unwrap_or_else(|why| {
+            error!("Failed to move playback to the restarted player: {why}");
+
+            false
+        });
+
+        if let (false, Some((playback_info, position))) = (transferred, resume) {
+            if let Err(why) = self.player.resume(playback_info, position).await {
+                error!("Failed to resume playback: {why}");
+            }
+        }
+
         Ok(())
     }
 
+    /// Pause playback when the host leaves the call, and keep the player around in case they come back.
+    ///
+    /// The player is only shut down if the host hasn't returned by the end of the grace period.
+    async fn host_left(&mut self) {
+        let grace_period = spoticord_config::host_grace_period();
+
+        if !self.active || self.standby_tx.is_some() {
+            return;
+        }
+
+        if grace_period.is_zero() {
+            self.shutdown_player().await;
+            return;
+        }
+
+        debug!("Owner of session disconnected, keeping player around for {grace_period:?}");
+
+        self.standby_playing = matches!(
+            self.player.playback_info().await,
+            Ok(Some(info)) if info.playing()
+        );
+
+        if self.standby_playing {
+            if let Err(why) = self.player.pause().await {
+                error!("Failed to pause playback for standby: {why}");
+            }
+        }
+
+        let (tx, rx) = oneshot::channel::<()>();
+        self.standby_tx = Some(tx);
+
+        let inner_tx = self.commands_inner_tx.clone();
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = rx => return,
+                _ = tokio::time::sleep(grace_period) => {}
+            };
+
+            _ = inner_tx.send(SessionCommand::StandbyExpired).await;
+        });
+    }
+
+    /// Pick up where we left off if the host rejoins the call during the grace period
+    async fn host_returned(&mut self, user_id: UserId) {
+        if user_id != self.owner {
+            return;
+        }
+
+        let Some(tx) = self.standby_tx.take() else {
+            return;
+        };
+
+        _ = tx.send(());
+
+        debug!("Owner of session returned, continuing playback");
+
+        if std::mem::take(&mut self.standby_playing) {
+            if let Err(why) = self.player.play().await {
+                error!("Failed to continue playback after standby: {why}");
+            }
+        }
+    }
+
     async fn shutdown_player(&mut self) {
+        if let Some(tx) = self.standby_tx.take() {
+            _ = tx.send(());
+        }
+
+        debug!(
+            "Audio stream of guild {} closed: {}",
+            self.guild_id,
+            self.player.stream_stats()
+        );
+
         self.player.shutdown().await;
         self.start_timeout();
 
         self.active = false;
 
+        // A pending reconnect gives up once it sees that the player is gone
+        self.reconnecting = false;
+        self.resume = None;
+
         // Remove owner from session manager
         self.session_manager
             .remove_session(SessionQuery::Owner(self.owner));
@@ -537,6 +1193,11 @@ impl Drop for Session {
             lyrics.abort();
         }
 
+        // Abort queue task
+        if let Some(queue) = self.queue_embed.take() {
+            queue.abort();
+        }
+
         // Clean up the session from the session manager
         // This is done in Drop::drop to ensure that the session always cleans up after itself
         //  even if something went wrong
@@ -550,6 +1211,66 @@ impl Drop for Session {
     }
 }
 
+/// Retrieve the player settings that were configured for the given server
+async fn player_settings(database: &Database, guild_id: GuildId) -> Result<PlayerSettings> {
+    let guild = database.get_guild(guild_id.to_string()).await.optional()?;
+
+    let kbps = guild
+        .as_ref()
+        .map_or(Quality::default().kbps(), |guild| guild.bitrate as u16);
+
+    let crossfade = guild.as_ref().map_or(0, |guild| guild.crossfade as u64);
+
+    let autoplay = guild.as_ref().is_some_and(|guild| guild.autoplay);
+    let equalizer = guild.as_ref().map_or(Preset::Flat, |guild| {
+        equalizer(guild.equalizer_preset(), &guild.equalizer_gains())
+    });
+    let (buffer_min, buffer_max) = spoticord_config::buffer_bounds();
+
+    let normalisation = guild
+        .filter(|guild| guild.normalisation)
+        .map(|guild| Normalisation {
+            album: guild.normalisation_mode() == NormalisationMode::Album,
+            pregain_db: guild.normalisation_pregain,
+            threshold_dbfs: guild.normalisation_threshold,
+        });
+
+    Ok(PlayerSettings {
+        quality: Quality::from_kbps(u16::min(kbps, spoticord_config::max_bitrate())),
+        normalisation,
+        crossfade: Duration::from_secs(crossfade),
+        fade: spoticord_config::fade_length(),
+        stall_threshold: spoticord_config::stall_threshold(),
+        autoplay,
+        buffer: BufferBounds {
+            min: buffer_min,
+            max: buffer_max,
+        },
+        equalizer,
+        ..Default::default()
+    })
+}
+
+/// Turn the equalizer settings of a guild into a preset, custom gains are only used by the custom preset
+pub fn equalizer(preset: EqualizerPreset, gains: &[f32]) -> Preset {
+    match preset {
+        EqualizerPreset::Flat => Preset::Flat,
+        EqualizerPreset::BassBoost => Preset::BassBoost,
+        EqualizerPreset::Vocal => Preset::Vocal,
+        EqualizerPreset::Treble => Preset::Treble,
+        EqualizerPreset::Loudness => Preset::Loudness,
+        EqualizerPreset::Custom => {
+            let mut custom = [0.0; BANDS];
+
+            for (band, gain) in custom.iter_mut().zip(gains) {
+                *band = *gain;
+            }
+
+            Preset::Custom(custom)
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SessionHandle {
     guild: GuildId,
@@ -617,6 +1338,96 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Transfer the host's active Spotify playback to this session's device.
+    ///
+    /// Returns `false` if the host currently isn't playing anything.
+    pub async fn transfer_playback(&self) -> anyhow::Result<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.commands
+            .send(SessionCommand::TransferPlayback(tx))
+            .await?;
+
+        Ok(rx.await??)
+    }
+
+    /// Retrieve the user who added the current track to the queue, if it was added using `/add`
+    pub async fn requester(&self) -> anyhow::Result<Option<UserId>> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(SessionCommand::GetRequester(tx)).await?;
+
+        let result = rx.await?;
+        Ok(result)
+    }
+
+    /// Check whether the current track was picked by autoplay
+    pub async fn autoplayed(&self) -> anyhow::Result<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.commands
+            .send(SessionCommand::GetAutoplayed(tx))
+            .await?;
+
+        let result = rx.await?;
+        Ok(result)
+    }
+
+    /// Retrieve the equalizer preset that the player is currently using
+    pub async fn equalizer(&self) -> anyhow::Result<Preset> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(SessionCommand::GetEqualizer(tx)).await?;
+
+        let result = rx.await?;
+        Ok(result)
+    }
+
+    /// Change the equalizer of the player, without interrupting playback
+    pub async fn set_equalizer(&self, preset: Preset) {
+        if let Err(why) = self
+            .commands
+            .send(SessionCommand::SetEqualizer(preset))
+            .await
+        {
+            error!("Failed to send command: {why}");
+        }
+    }
+
+    /// Retrieve how fast the player is playing audio, relative to normal speed
+    pub async fn speed(&self) -> anyhow::Result<f32> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(SessionCommand::GetSpeed(tx)).await?;
+
+        let result = rx.await?;
+        Ok(result)
+    }
+
+    /// Play audio faster or slower, without interrupting playback
+    pub async fn set_speed(&self, speed: f32) -> std::result::Result<(), PlayerError> {
+        let (tx, rx) = oneshot::channel();
+        self.commands
+            .send(SessionCommand::SetSpeed(speed, tx))
+            .await
+            .map_err(|_| PlayerError::Shutdown)?;
+
+        rx.await.map_err(|_| PlayerError::Shutdown)?
+    }
+
+    /// Add a track to the queue of the host on behalf of another user.
+    ///
+    /// The requester will be shown in the playback embed once the track starts playing.
+    pub async fn add_to_queue(
+        &self,
+        track_id: impl Into<String>,
+        requester: UserId,
+    ) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.commands
+            .send(SessionCommand::AddToQueue(track_id.into(), requester, tx))
+            .await?;
+
+        rx.await??;
+
+        Ok(())
+    }
+
     /// Create a playback embed as a response to an interaction
     ///
     /// This playback embed will automatically update when certain events happen
@@ -647,6 +1458,17 @@ impl SessionHandle {
         Ok(())
     }
 
+    /// Create a queue embed as a response to an interaction
+    ///
+    /// This queue embed will automatically update whenever the current track changes
+    pub async fn create_queue_embed(&self, interaction: CommandInteraction) -> anyhow::Result<()> {
+        self.commands
+            .send(SessionCommand::CreateQueueEmbed(self.clone(), interaction))
+            .await?;
+
+        Ok(())
+    }
+
     /// Instruct the session to destroy the player (but keep voice call).
     ///
     /// This is meant to be used for when the session owner leaves the call
@@ -659,6 +1481,28 @@ impl SessionHandle {
         }
     }
 
+    /// Inform the session that the owner has left the call.
+    ///
+    /// Playback is paused, and the player is destroyed if the owner doesn't return within the grace period.
+    pub async fn host_left(&self) {
+        if let Err(why) = self.commands.send(SessionCommand::HostLeft).await {
+            error!("Failed to send command: {why}");
+        }
+    }
+
+    /// Inform the session that a user has joined its voice channel.
+    ///
+    /// If this is the owner returning within the grace period, playback continues.
+    pub async fn host_returned(&self, user_id: UserId) {
+        if let Err(why) = self
+            .commands
+            .send(SessionCommand::HostReturned(user_id))
+            .await
+        {
+            error!("Failed to send command: {why}");
+        }
+    }
+
     /// Instruct the session to destroy itself.
     ///
     /// This should also remove the player and the owner from the session manager.
@@ -692,11 +1536,7 @@ impl songbird::EventHandler for SessionHandle {
                 }
 
                 match self.owner().await {
-                    Ok(id) if id.get() == user_id.0 => {
-                        debug!("Owner of session disconnected, stopping playback");
-
-                        self.shutdown_player().await;
-                    }
+                    Ok(id) if id.get() == user_id.0 => self.host_left().await,
                     _ => {}
                 }
             }
@@ -19,13 +19,18 @@ use serenity::{
 };
 use spoticord_player::info::PlaybackInfo;
 use spoticord_utils::discord::Colors;
-use tokio::task::JoinHandle;
+use tokio::{sync::mpsc, task::JoinHandle};
 
 use crate::{Session, SessionHandle};
 
 const PAGE_LENGTH: usize = 3000;
 const TIME_OFFSET: u32 = 1000;
 
+#[derive(Debug)]
+pub enum Command {
+    InvokeUpdate,
+}
+
 pub struct LyricsEmbed {
     guild_id: String,
     ctx: Context,
@@ -35,6 +40,8 @@ pub struct LyricsEmbed {
 
     lyrics: Option<Lyrics>,
     page: usize,
+
+    rx: mpsc::Receiver<Command>,
 }
 
 impl LyricsEmbed {
@@ -42,7 +49,7 @@ impl LyricsEmbed {
         session: &Session,
         handle: SessionHandle,
         interaction: CommandInteraction,
-    ) -> Result<Option<JoinHandle<()>>> {
+    ) -> Result<Option<LyricsEmbedHandle>> {
         let ctx = session.context.clone();
 
         if !session.active {
@@ -78,6 +85,7 @@ impl LyricsEmbed {
         // Retrieve message instead of editing interaction response, as those tokens are only valid for 15 minutes
         let message = interaction.get_response(&ctx).await?;
 
+        let (tx, rx) = mpsc::channel(16);
         let this = Self {
             guild_id: guild_id.clone(),
             ctx: ctx.clone(),
@@ -87,6 +95,8 @@ impl LyricsEmbed {
 
             lyrics,
             page: 0,
+
+            rx,
         };
 
         let collector = ComponentInteractionCollector::new(&ctx)
@@ -98,9 +108,9 @@ impl LyricsEmbed {
             })
             .timeout(Duration::from_secs(3600 * 24));
 
-        let handle = tokio::spawn(this.run(collector));
+        let task = tokio::spawn(this.run(collector));
 
-        Ok(Some(handle))
+        Ok(Some(LyricsEmbedHandle { tx, task }))
     }
 
     async fn run(mut self, collector: ComponentInteractionCollector) {
@@ -115,6 +125,16 @@ impl LyricsEmbed {
                     }
                 }
 
+                opt_command = self.rx.recv() => {
+                    let Some(Command::InvokeUpdate) = opt_command else {
+                        break;
+                    };
+
+                    if self.handle_tick().await.is_break() {
+                        break;
+                    }
+                }
+
                 opt_press = stream.next() => {
                     let Some(press) = opt_press else {
                         break;
@@ -285,6 +305,28 @@ impl LyricsEmbed {
     }
 }
 
+pub struct LyricsEmbedHandle {
+    tx: mpsc::Sender<Command>,
+    task: JoinHandle<()>,
+}
+
+impl LyricsEmbedHandle {
+    pub fn is_valid(&self) -> bool {
+        !self.tx.is_closed()
+    }
+
+    /// Re-check the playback position right away, instead of waiting for the next tick
+    pub async fn invoke_update(&self) -> Result<()> {
+        self.tx.send(Command::InvokeUpdate).await?;
+
+        Ok(())
+    }
+
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
 async fn respond_not_playing(context: &Context, interaction: CommandInteraction) -> Result<()> {
     interaction
         .create_response(
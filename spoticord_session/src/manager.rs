@@ -3,6 +3,7 @@ use crate::error::Result;
 use serenity::all::{ChannelId, GuildId, UserId};
 use songbird::Songbird;
 use spoticord_database::Database;
+use spoticord_player::cache::AudioCache;
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
@@ -12,6 +13,7 @@ use std::{
 pub struct SessionManager {
     songbird: Arc<Songbird>,
     database: Database,
+    audio_cache: Option<AudioCache>,
 
     sessions: Arc<Mutex<HashMap<GuildId, SessionHandle>>>,
     owners: Arc<Mutex<HashMap<UserId, SessionHandle>>>,
@@ -23,10 +25,15 @@ pub enum SessionQuery {
 }
 
 impl SessionManager {
-    pub fn new(songbird: Arc<Songbird>, database: Database) -> Self {
+    pub fn new(
+        songbird: Arc<Songbird>,
+        database: Database,
+        audio_cache: Option<AudioCache>,
+    ) -> Self {
         Self {
             songbird,
             database,
+            audio_cache,
 
             sessions: Arc::new(Mutex::new(HashMap::new())),
             owners: Arc::new(Mutex::new(HashMap::new())),
@@ -122,4 +129,9 @@ impl SessionManager {
     pub fn database(&self) -> Database {
         self.database.clone()
     }
+
+    /// The audio cache that is shared between all sessions, if caching is enabled
+    pub fn audio_cache(&self) -> Option<AudioCache> {
+        self.audio_cache.clone()
+    }
 }
@@ -6,17 +6,24 @@ use serenity::{
         ButtonStyle, CommandInteraction, ComponentInteraction, ComponentInteractionCollector,
         Context, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter,
         CreateInteractionResponse, CreateInteractionResponseFollowup,
-        CreateInteractionResponseMessage, CreateMessage, EditMessage, Message, User,
+        CreateInteractionResponseMessage, CreateMessage, EditMessage, Message, User, UserId,
     },
     futures::StreamExt,
 };
-use spoticord_player::{info::PlaybackInfo, PlayerHandle};
+use spoticord_player::{
+    equalizer::Preset,
+    info::{PlaybackInfo, RepeatMode},
+    PlayerHandle,
+};
 use spoticord_utils::discord::Colors;
 use std::{ops::ControlFlow, time::Duration};
 use tokio::{sync::mpsc, time::Instant};
 
 use crate::{Session, SessionHandle};
 
+/// The amount of milliseconds the seek buttons jump back or forward
+const SEEK_STEP: u32 = 15_000;
+
 #[derive(Debug)]
 pub enum Command {
     InvokeUpdate(bool),
@@ -90,7 +97,13 @@ impl PlaybackEmbed {
                 &ctx,
                 CreateInteractionResponse::Message(
                     CreateInteractionResponseMessage::new()
-                        .embed(build_embed(&playback_info, &owner))
+                        .embed(build_embed(
+                            &playback_info,
+                            &owner,
+                            session.requester,
+                            session.autoplayed,
+                            session.settings.equalizer,
+                        ))
                         .components(vec![build_buttons(ctx_id, playback_info.playing())]),
                 ),
             )
@@ -221,9 +234,22 @@ impl PlaybackEmbed {
             return;
         }
 
-        match press.data.custom_id.split('-').last() {
+        let result = match press.data.custom_id.split('-').last() {
             Some("next") => player.next_track().await,
             Some("prev") => player.previous_track().await,
+            Some("rewind") => {
+                player
+                    .seek(playback_info.current_position().saturating_sub(SEEK_STEP))
+                    .await
+            }
+            Some("forward") => {
+                player
+                    .seek(u32::min(
+                        playback_info.current_position() + SEEK_STEP,
+                        playback_info.duration(),
+                    ))
+                    .await
+            }
             Some("pause") => {
                 if playback_info.playing() {
                     player.pause().await
@@ -232,7 +258,27 @@ impl PlaybackEmbed {
                 }
             }
 
-            _ => {}
+            _ => Ok(()),
+        };
+
+        if let Err(why) = result {
+            _ = press
+                .create_response(
+                    &self.ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .embed(
+                                CreateEmbed::new()
+                                    .title("Cannot perform action")
+                                    .description(why.to_string())
+                                    .color(Colors::Error),
+                            )
+                            .ephemeral(true),
+                    ),
+                )
+                .await;
+
+            return;
         }
 
         _ = press
@@ -281,6 +327,10 @@ impl PlaybackEmbed {
             }
         };
 
+        let requester = self.session.requester().await.ok().flatten();
+        let autoplayed = self.session.autoplayed().await.unwrap_or(false);
+        let equalizer = self.session.equalizer().await.unwrap_or_default();
+
         let should_pin = !force_edit && self.update_behavior.is_pinned();
 
         if should_pin {
@@ -292,7 +342,13 @@ impl PlaybackEmbed {
                 .send_message(
                     &self.ctx,
                     CreateMessage::new()
-                        .embed(build_embed(&playback_info, &owner))
+                        .embed(build_embed(
+                            &playback_info,
+                            &owner,
+                            requester,
+                            autoplayed,
+                            equalizer,
+                        ))
                         .components(vec![build_buttons(self.id, playback_info.playing())]),
                 )
                 .await
@@ -309,7 +365,13 @@ impl PlaybackEmbed {
             .edit(
                 &self.ctx,
                 EditMessage::new()
-                    .embed(build_embed(&playback_info, &owner))
+                    .embed(build_embed(
+                        &playback_info,
+                        &owner,
+                        requester,
+                        autoplayed,
+                        equalizer,
+                    ))
                     .components(vec![build_buttons(self.id, playback_info.playing())]),
             )
             .await
@@ -383,20 +445,32 @@ fn not_playing_embed() -> CreateEmbed {
         .color(Colors::Error)
 }
 
-fn build_embed(playback_info: &PlaybackInfo, owner: &User) -> CreateEmbed {
+fn build_embed(
+    playback_info: &PlaybackInfo,
+    owner: &User,
+    requester: Option<UserId>,
+    autoplayed: bool,
+    equalizer: Preset,
+) -> CreateEmbed {
     let mut description = String::new();
 
-    description += &format!("## [{}]({})\n", playback_info.name(), playback_info.url());
+    description += &match playback_info.url() {
+        Some(url) => format!("## [{}]({url})", playback_info.name()),
+        None => format!("## {}", playback_info.name()),
+    };
+
+    if playback_info.explicit() {
+        description += " :regional_indicator_e:";
+    }
+
+    description += "\n";
 
     if let Some(artists) = playback_info.artists() {
         let artists = artists
             .iter()
-            .map(|artist| {
-                format!(
-                    "[{}](https://open.spotify.com/artist/{})",
-                    artist.name,
-                    artist.id.to_base62().expect("invalid artist")
-                )
+            .map(|artist| match artist.id.to_base62() {
+                Ok(id) => format!("[{}](https://open.spotify.com/artist/{id})", artist.name),
+                Err(_) => artist.name.clone(),
             })
             .collect::<Vec<_>>()
             .join(", ");
@@ -405,17 +479,33 @@ fn build_embed(playback_info: &PlaybackInfo, owner: &User) -> CreateEmbed {
     }
 
     if let Some(album_name) = playback_info.album_name() {
-        description += &format!("Album: **{album_name}**\n");
+        description += &format!("Album: **{album_name}**");
+
+        if let Some(year) = playback_info.release_year() {
+            description += &format!(" ({year})");
+        }
+
+        description += "\n";
     }
 
     if let Some(show_name) = playback_info.show_name() {
-        description += &format!("On {show_name}\n");
+        description += &format!("On {show_name}");
+
+        if let Some(publisher) = playback_info.publisher().filter(|name| !name.is_empty()) {
+            description += &format!(" by {publisher}");
+        }
+
+        if let Some(year) = playback_info.release_year() {
+            description += &format!(" ({year})");
+        }
+
+        description += "\n";
     }
 
     description += "\n";
 
     let position = playback_info.current_position();
-    let index = position * 20 / playback_info.duration();
+    let index = position * 20 / playback_info.duration().max(1);
 
     description += if playback_info.playing() {
         "▶️ "
@@ -438,6 +528,32 @@ fn build_embed(playback_info: &PlaybackInfo, owner: &User) -> CreateEmbed {
         spoticord_utils::time_to_string(playback_info.duration() / 1000)
     );
 
+    description += &format!("\n:loud_sound: {}%", playback_info.volume_percent());
+
+    if playback_info.shuffle() {
+        description += " | :twisted_rightwards_arrows: Shuffle";
+    }
+
+    match playback_info.repeat() {
+        RepeatMode::Off => {}
+        RepeatMode::Context => description += " | :repeat: Repeat",
+        RepeatMode::Track => description += " | :repeat_one: Repeat song",
+    }
+
+    if playback_info.speed() != 1.0 {
+        description += &format!(" | :stopwatch: {}x", playback_info.speed());
+    }
+
+    if !equalizer.is_flat() {
+        description += &format!(" | :level_slider: {}", equalizer.name());
+    }
+
+    if let Some(requester) = requester {
+        description += &format!("\n:bust_in_silhouette: Requested by <@{requester}>");
+    } else if autoplayed {
+        description += "\n:radio: Autoplay";
+    }
+
     CreateEmbed::new()
         .author(
             CreateEmbedAuthor::new("Currently Playing")
@@ -446,8 +562,12 @@ fn build_embed(playback_info: &PlaybackInfo, owner: &User) -> CreateEmbed {
         .description(description)
         .thumbnail(playback_info.thumbnail())
         .footer(
-            CreateEmbedFooter::new(owner.global_name.as_ref().unwrap_or(&owner.name))
-                .icon_url(owner.face()),
+            CreateEmbedFooter::new(format!(
+                "{} • {}kbps",
+                owner.global_name.as_ref().unwrap_or(&owner.name),
+                playback_info.quality().kbps()
+            ))
+            .icon_url(owner.face()),
         )
         .color(Colors::Info)
 }
@@ -456,6 +576,8 @@ fn build_buttons(id: u64, playing: bool) -> CreateActionRow {
     let prev_button_id = format!("{id}-prev");
     let next_button_id = format!("{id}-next");
     let pause_button_id = format!("{id}-pause");
+    let rewind_button_id = format!("{id}-rewind");
+    let forward_button_id = format!("{id}-forward");
 
     let prev_button = CreateButton::new(prev_button_id)
         .style(ButtonStyle::Primary)
@@ -465,6 +587,14 @@ fn build_buttons(id: u64, playing: bool) -> CreateActionRow {
         .style(ButtonStyle::Primary)
         .label(">>");
 
+    let rewind_button = CreateButton::new(rewind_button_id)
+        .style(ButtonStyle::Secondary)
+        .label("-15s");
+
+    let forward_button = CreateButton::new(forward_button_id)
+        .style(ButtonStyle::Secondary)
+        .label("+15s");
+
     let pause_button = CreateButton::new(pause_button_id)
         .style(if playing {
             ButtonStyle::Danger
@@ -473,5 +603,11 @@ fn build_buttons(id: u64, playing: bool) -> CreateActionRow {
         })
         .label(if playing { "Pause" } else { "Play" });
 
-    CreateActionRow::Buttons(vec![prev_button, pause_button, next_button])
+    CreateActionRow::Buttons(vec![
+        prev_button,
+        rewind_button,
+        pause_button,
+        forward_button,
+        next_button,
+    ])
 }
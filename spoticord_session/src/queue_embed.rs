@@ -0,0 +1,343 @@
+use std::{ops::ControlFlow, time::Duration};
+
+use anyhow::Result;
+use log::error;
+use serenity::{
+    all::{
+        CommandInteraction, ComponentInteraction, ComponentInteractionCollector, Context,
+        CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+        CreateInteractionResponseMessage, EditInteractionResponse, EditMessage, Message,
+    },
+    futures::StreamExt,
+};
+use spoticord_database::Database;
+use spoticord_player::info::PlaybackInfo;
+use spoticord_utils::discord::{escape, Colors};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{
+    spotify::{self, QueueItem},
+    Session, SessionHandle,
+};
+
+/// The amount of upcoming tracks that are shown on a single page
+const PAGE_SIZE: usize = 10;
+
+#[derive(Debug)]
+pub enum Command {
+    InvokeUpdate,
+}
+
+pub struct QueueEmbed {
+    guild_id: String,
+    ctx: Context,
+    session: SessionHandle,
+    database: Database,
+    message: Message,
+
+    playback_info: PlaybackInfo,
+    queue: Vec<QueueItem>,
+    page: usize,
+
+    rx: mpsc::Receiver<Command>,
+}
+
+impl QueueEmbed {
+    pub async fn create(
+        session: &Session,
+        handle: SessionHandle,
+        interaction: CommandInteraction,
+    ) -> Result<Option<QueueEmbedHandle>> {
+        let ctx = session.context.clone();
+
+        if !session.active {
+            respond_not_playing(&ctx, interaction).await?;
+
+            return Ok(None);
+        }
+
+        let Some(playback_info) = session.player.playback_info().await? else {
+            respond_not_playing(&ctx, interaction).await?;
+
+            return Ok(None);
+        };
+
+        let guild_id = interaction
+            .guild_id
+            .expect("interaction was outside of a guild")
+            .to_string();
+        let database = session.session_manager.database();
+        let owner = session.owner.to_string();
+
+        // Retrieving the queue goes through the Spotify API, which may take longer than Discord is willing to wait
+        interaction.defer(&ctx).await?;
+
+        let (tx, rx) = mpsc::channel(16);
+        let task = tokio::spawn(async move {
+            let queue = match spotify::queue(&database, owner).await {
+                Ok(queue) => queue,
+                Err(why) => {
+                    error!("Failed to retrieve queue: {why}");
+
+                    vec![]
+                }
+            };
+
+            // Keep the message around, as interaction tokens are only valid for 15 minutes
+            let message = match interaction
+                .edit_response(
+                    &ctx,
+                    EditInteractionResponse::new()
+                        .embed(queue_embed(&playback_info, &queue, 0))
+                        .components(vec![queue_buttons(&guild_id, &queue, 0)]),
+                )
+                .await
+            {
+                Ok(message) => message,
+                Err(why) => {
+                    error!("Failed to send queue: {why}");
+
+                    return;
+                }
+            };
+
+            let this = Self {
+                guild_id: guild_id.clone(),
+                ctx: ctx.clone(),
+                session: handle,
+                database,
+                message,
+
+                playback_info,
+                queue,
+                page: 0,
+
+                rx,
+            };
+
+            let collector = ComponentInteractionCollector::new(&ctx)
+                .filter(move |press| {
+                    let parts = press.data.custom_id.split(':').collect::<Vec<_>>();
+
+                    matches!(parts.first(), Some(&"queue"))
+                        && matches!(parts.last(), Some(id) if id == &guild_id)
+                })
+                .timeout(Duration::from_secs(3600 * 24));
+
+            this.run(collector).await;
+        });
+
+        Ok(Some(QueueEmbedHandle { tx, task }))
+    }
+
+    async fn run(mut self, collector: ComponentInteractionCollector) {
+        let mut stream = collector.stream();
+
+        loop {
+            tokio::select! {
+                opt_command = self.rx.recv() => {
+                    let Some(Command::InvokeUpdate) = opt_command else {
+                        break;
+                    };
+
+                    if self.refresh().await.is_break() {
+                        break;
+                    }
+                }
+
+                opt_press = stream.next() => {
+                    let Some(press) = opt_press else {
+                        break;
+                    };
+
+                    // Immediately acknowledge, we don't have to inform the user about the update
+                    _ = press
+                        .create_response(&self.ctx, CreateInteractionResponse::Acknowledge)
+                        .await;
+
+                    if self.handle_press(press).await.is_break() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Retrieve the current track and the upcoming tracks again, and go back to the first page
+    async fn refresh(&mut self) -> ControlFlow<(), ()> {
+        let Ok(player) = self.session.player().await else {
+            // Failure means that the session is gone, so we quit
+            return ControlFlow::Break(());
+        };
+
+        let Ok(owner) = self.session.owner().await else {
+            return ControlFlow::Break(());
+        };
+
+        let Ok(Some(playback_info)) = player.playback_info().await else {
+            // If we're not playing anything, just wait until we are
+            return ControlFlow::Continue(());
+        };
+
+        self.queue = match spotify::queue(&self.database, owner.to_string()).await {
+            Ok(queue) => queue,
+            Err(why) => {
+                error!("Failed to retrieve queue: {why}");
+
+                return ControlFlow::Continue(());
+            }
+        };
+        self.playback_info = playback_info;
+        self.page = 0;
+
+        self.update_message().await
+    }
+
+    async fn handle_press(&mut self, press: ComponentInteraction) -> ControlFlow<(), ()> {
+        let next = match press.data.custom_id.split(':').nth(1) {
+            Some("next") => true,
+            Some("prev") => false,
+            _ => return ControlFlow::Continue(()),
+        };
+
+        match next {
+            true if self.page < page_count(&self.queue) - 1 => self.page += 1,
+            false if self.page > 0 => self.page -= 1,
+            _ => return ControlFlow::Continue(()),
+        }
+
+        self.update_message().await
+    }
+
+    async fn update_message(&mut self) -> ControlFlow<(), ()> {
+        if let Err(why) = self
+            .message
+            .edit(
+                &self.ctx,
+                EditMessage::new()
+                    .embed(queue_embed(&self.playback_info, &self.queue, self.page))
+                    .components(vec![queue_buttons(&self.guild_id, &self.queue, self.page)]),
+            )
+            .await
+        {
+            error!("Failed to update queue: {why}");
+
+            return ControlFlow::Break(());
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+pub struct QueueEmbedHandle {
+    tx: mpsc::Sender<Command>,
+    task: JoinHandle<()>,
+}
+
+impl QueueEmbedHandle {
+    pub fn is_valid(&self) -> bool {
+        !self.tx.is_closed()
+    }
+
+    /// Retrieve the queue again, for example after the track has changed
+    pub async fn invoke_update(&self) -> Result<()> {
+        self.tx.send(Command::InvokeUpdate).await?;
+
+        Ok(())
+    }
+
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+async fn respond_not_playing(context: &Context, interaction: CommandInteraction) -> Result<()> {
+    interaction
+        .create_response(
+            context,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(not_playing_embed())
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+fn not_playing_embed() -> CreateEmbed {
+    CreateEmbed::new()
+        .title("Cannot show queue")
+        .description("I'm currently not playing any music in this server.")
+        .color(Colors::Error)
+}
+
+fn page_count(queue: &[QueueItem]) -> usize {
+    usize::max(queue.len().div_ceil(PAGE_SIZE), 1)
+}
+
+fn queue_embed(playback_info: &PlaybackInfo, queue: &[QueueItem], page: usize) -> CreateEmbed {
+    let mut description = String::new();
+
+    description += "**Now playing**\n";
+    description += &match playback_info.url() {
+        Some(url) => format!("[{}]({url})", escape(playback_info.name())),
+        None => escape(playback_info.name()),
+    };
+
+    if let Some(artists) = playback_info.artists() {
+        let artists = artists
+            .iter()
+            .map(|artist| artist.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        description += &format!(" - {}", escape(artists));
+    } else if let Some(show_name) = playback_info.show_name() {
+        description += &format!(" - {}", escape(show_name));
+    }
+
+    description += "\n\n**Up next**\n";
+
+    if queue.is_empty() {
+        description += "Nothing is queued up right now.";
+    }
+
+    for (i, item) in queue
+        .iter()
+        .enumerate()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+    {
+        let name = escape(&item.name);
+
+        match &item.url {
+            Some(url) => description += &format!("{}. [{name}]({url})", i + 1),
+            None => description += &format!("{}. {name}", i + 1),
+        }
+
+        description += &format!(" - {}\n", escape(&item.creators));
+    }
+
+    CreateEmbed::new()
+        .title("Queue")
+        .description(description)
+        .footer(CreateEmbedFooter::new(format!(
+            "Page {} of {}",
+            page + 1,
+            page_count(queue)
+        )))
+        .color(Colors::Info)
+}
+
+fn queue_buttons(id: &str, queue: &[QueueItem], page: usize) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("queue:prev:{id}"))
+            .disabled(page == 0)
+            .label("<"),
+        CreateButton::new(format!("queue:next:{id}"))
+            .disabled(page >= page_count(queue) - 1)
+            .label(">"),
+    ])
+}
@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use rspotify::{
+    clients::{BaseClient, OAuthClient},
+    model::{AdditionalType, PlayableId, PlayableItem, SearchResult, SearchType, TrackId},
+    prelude::Id,
+    AuthCodeSpotify, Token,
+};
+use spoticord_database::Database;
+
+use crate::error::Result;
+
+/// Create a Spotify Web API client that acts on behalf of the given user
+pub async fn client(database: &Database, user_id: impl AsRef<str>) -> Result<AuthCodeSpotify> {
+    let access_token = database.get_access_token(user_id).await?;
+
+    Ok(spoticord_config::get_spotify(Token {
+        access_token,
+        ..Default::default()
+    }))
+}
+
+/// Transfer the active playback of a user to the given device.
+///
+/// Returns `false` if the user currently has nothing playing on any device.
+pub async fn transfer_playback(
+    database: &Database,
+    user_id: impl AsRef<str>,
+    device_id: &str,
+) -> Result<bool> {
+    let spotify = client(database, user_id).await?;
+
+    // A paused device keeps its playback, transferring it would resume it on this device
+    if !spotify
+        .current_playback(None, None::<Vec<&AdditionalType>>)
+        .await?
+        .is_some_and(|playback| playback.is_playing)
+    {
+        return Ok(false);
+    }
+
+    // The device might not be visible to the Web API yet right after it has registered itself
+    let mut tries = 0;
+
+    loop {
+        match spotify.transfer_playback(device_id, Some(true)).await {
+            Ok(()) => return Ok(true),
+            Err(why) => {
+                tries += 1;
+                if tries >= 3 {
+                    return Err(why.into());
+                }
+
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+/// Retrieve the URI of the context (album, playlist, etc.) that the user is currently playing from
+pub async fn playback_context(
+    database: &Database,
+    user_id: impl AsRef<str>,
+) -> Result<Option<String>> {
+    let spotify = client(database, user_id).await?;
+    let playback = spotify
+        .current_playback(None, None::<Vec<&AdditionalType>>)
+        .await?;
+
+    Ok(playback
+        .and_then(|playback| playback.context)
+        .map(|context| context.uri))
+}
+
+/// A single track or episode inside of a user's queue
+#[derive(Debug, Clone)]
+pub struct QueueItem {
+    pub name: String,
+
+    /// The artists of a track, or the show of an episode
+    pub creators: String,
+    pub url: Option<String>,
+}
+
+impl From<PlayableItem> for QueueItem {
+    fn from(value: PlayableItem) -> Self {
+        match value {
+            PlayableItem::Track(track) => Self {
+                url: track
+                    .id
+                    .map(|id| format!("https://open.spotify.com/track/{}", id.id())),
+                creators: track
+                    .artists
+                    .into_iter()
+                    .map(|artist| artist.name)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                name: track.name,
+            },
+            PlayableItem::Episode(episode) => Self {
+                url: Some(format!(
+                    "https://open.spotify.com/episode/{}",
+                    episode.id.id()
+                )),
+                creators: episode.show.name,
+                name: episode.name,
+            },
+        }
+    }
+}
+
+/// Retrieve the upcoming tracks in the queue of a user
+pub async fn queue(database: &Database, user_id: impl AsRef<str>) -> Result<Vec<QueueItem>> {
+    let spotify = client(database, user_id).await?;
+    let queue = spotify.current_user_queue().await?;
+
+    Ok(queue.queue.into_iter().map(QueueItem::from).collect())
+}
+
+/// A track that came up when searching Spotify
+#[derive(Debug, Clone)]
+pub struct SearchItem {
+    /// The base62 id of the track
+    pub id: String,
+    pub name: String,
+    pub artists: String,
+}
+
+/// Search Spotify for tracks matching the query, on behalf of the given user
+pub async fn search_tracks(
+    database: &Database,
+    user_id: impl AsRef<str>,
+    query: &str,
+) -> Result<Vec<SearchItem>> {
+    let spotify = client(database, user_id).await?;
+    let SearchResult::Tracks(page) = spotify
+        .search(query, SearchType::Track, None, None, Some(10), None)
+        .await?
+    else {
+        return Ok(vec![]);
+    };
+
+    Ok(page
+        .items
+        .into_iter()
+        .filter_map(|track| {
+            Some(SearchItem {
+                id: track.id?.id().to_string(),
+                artists: track
+                    .artists
+                    .into_iter()
+                    .map(|artist| artist.name)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                name: track.name,
+            })
+        })
+        .collect())
+}
+
+/// Add a track to the end of the queue of the given user
+pub async fn add_to_queue(
+    database: &Database,
+    user_id: impl AsRef<str>,
+    track_id: &str,
+    device_id: &str,
+) -> Result<()> {
+    let spotify = client(database, user_id).await?;
+    let track_id = TrackId::from_id(track_id)?;
+
+    spotify
+        .add_item_to_queue(PlayableId::Track(track_id), Some(device_id))
+        .await?;
+
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
-use log::{debug, info};
+use log::{debug, error, info};
 use poise::{serenity_prelude, Framework, FrameworkContext, FrameworkOptions};
 use serenity::all::{ActivityData, FullEvent, Interaction, Ready, ShardManager, GuildId, Command};
 use spoticord_database::Database;
-use spoticord_session::manager::SessionManager;
+use spoticord_player::cache::AudioCache;
+use spoticord_session::manager::{SessionManager, SessionQuery};
 
 use crate::commands;
 // OPTIONAL: if you want /tone, uncomment the next line and keep tone.rs present
@@ -25,9 +26,22 @@ pub fn framework_opts() -> FrameworkOptions<Data, anyhow::Error> {
         commands: vec![
             commands::core::help(),
             commands::core::link(),
+            commands::core::autotransfer(),
             commands::music::join(),
             commands::music::disconnect(),
             commands::music::playing(),
+            commands::music::play(),
+            commands::music::queue(),
+            commands::music::add(),
+            commands::music::volume(),
+            commands::music::eq(),
+            commands::music::speed(),
+            commands::music::seek(),
+            commands::music::shuffle(),
+            commands::music::repeat(),
+            commands::settings::settings(),
+            commands::admin::cache(),
+            commands::admin::buffers(),
             // OPTIONAL extras you can re-enable:
             // commands::core::version(),
             // commands::core::rename(),
@@ -70,7 +84,7 @@ pub async fn setup(
     let songbird = songbird::get(ctx)
         .await
         .ok_or_else(|| anyhow!("Songbird was not registered during setup"))?;
-    let manager = SessionManager::new(songbird, database);
+    let manager = SessionManager::new(songbird, database, audio_cache());
 
     #[cfg(feature = "stats")]
     let stats = StatsManager::new(spoticord_config::kv_url())?;
@@ -85,11 +99,29 @@ pub async fn setup(
     Ok(manager)
 }
 
+/// Open the audio cache if one has been configured, playback works fine without it
+fn audio_cache() -> Option<AudioCache> {
+    let directory = spoticord_config::cache_dir()?;
+
+    match AudioCache::new(directory, spoticord_config::cache_size_limit()) {
+        Ok(cache) => {
+            info!("Caching audio in {directory}");
+
+            Some(cache)
+        }
+        Err(why) => {
+            error!("Failed to open audio cache, continuing without: {why}");
+
+            None
+        }
+    }
+}
+
 async fn event_handler(
     ctx: &serenity_prelude::Context,
     event: &FullEvent,
     _framework: FrameworkContext<'_, Data, anyhow::Error>,
-    _data: &Data,
+    data: &Data,
 ) -> Result<()> {
     match event {
         FullEvent::Ready { data_about_bot } => {
@@ -106,6 +138,17 @@ async fn event_handler(
             }
         }
 
+        // Lets the host pick up where they left off when they rejoin the call
+        FullEvent::VoiceStateUpdate { new, .. } => {
+            if let (Some(guild_id), Some(channel_id)) = (new.guild_id, new.channel_id) {
+                if let Some(session) = data.get_session(SessionQuery::Guild(guild_id)) {
+                    if session.voice_channel() == channel_id {
+                        session.host_returned(new.user_id).await;
+                    }
+                }
+            }
+        }
+
         _ => {}
     }
     Ok(())
@@ -116,9 +159,6 @@ async fn background_loop(
     shard_manager: Arc<ShardManager>,
     #[cfg(feature = "stats")] mut stats_manager: spoticord_stats::StatsManager,
 ) {
-    #[cfg(feature = "stats")]
-    use log::error;
-
     loop {
         tokio::select! {
             _ = tokio::time::sleep(std::time::Duration::from_secs(60)) => {
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_utils::discord::Colors;
+
+use super::format_bytes;
+use crate::bot::Context;
+
+/// Discord doesn't allow more fields than this in a single embed
+const MAX_FIELDS: usize = 25;
+
+/// Show how well audio is flowing in every active session
+#[poise::command(slash_command, owners_only)]
+pub async fn buffers(ctx: Context<'_>) -> Result<()> {
+    let mut streams = vec![];
+
+    for session in ctx.data().get_all_sessions() {
+        let Ok(player) = session.player().await else {
+            continue;
+        };
+
+        streams.push((session.guild(), player.stream_stats()));
+    }
+
+    // Sessions with the most trouble first
+    streams.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.underruns));
+
+    let mut embed = CreateEmbed::new()
+        .title("Audio buffers")
+        .description(format!("{} active sessions", streams.len()))
+        .color(Colors::Info);
+
+    for (guild, stats) in streams.into_iter().take(MAX_FIELDS) {
+        embed = embed.field(
+            format!("Guild {guild}"),
+            format!(
+                "Underruns: {}\nSilence: {}\nPeak fill: {} / {}",
+                stats.underruns,
+                format_bytes(stats.silence_bytes),
+                format_bytes(stats.peak_fill as u64),
+                format_bytes(stats.target as u64),
+            ),
+            true,
+        );
+    }
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_player::cache::AudioCache;
+use spoticord_utils::discord::Colors;
+
+use super::format_bytes;
+use crate::bot::Context;
+
+/// Manage the audio cache of the bot
+#[poise::command(
+    slash_command,
+    owners_only,
+    subcommands("stats", "clear"),
+    subcommand_required
+)]
+pub async fn cache(_ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Show how well the audio cache is doing
+#[poise::command(slash_command, owners_only)]
+pub async fn stats(ctx: Context<'_>) -> Result<()> {
+    let Some(cache) = audio_cache(&ctx).await? else {
+        return Ok(());
+    };
+
+    let stats = cache.stats();
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title("Audio cache")
+                    .field("Size", format_bytes(stats.size), true)
+                    .field("Bytes saved", format_bytes(stats.bytes_saved), true)
+                    .field(
+                        "Hit ratio",
+                        format!(
+                            "{:.1}% ({} hits, {} misses)",
+                            stats.hit_ratio() * 100.0,
+                            stats.hits,
+                            stats.misses
+                        ),
+                        false,
+                    )
+                    .color(Colors::Info),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remove all audio files from the cache
+#[poise::command(slash_command, owners_only)]
+pub async fn clear(ctx: Context<'_>) -> Result<()> {
+    let Some(cache) = audio_cache(&ctx).await? else {
+        return Ok(());
+    };
+
+    ctx.defer_ephemeral().await?;
+
+    let freed = tokio::task::spawn_blocking(move || cache.clear()).await?;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title("Audio cache cleared")
+                    .description(format!("Freed up {}.", format_bytes(freed)))
+                    .color(Colors::Success),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Retrieve the audio cache, or inform the user that caching is disabled
+async fn audio_cache(ctx: &Context<'_>) -> Result<Option<AudioCache>> {
+    let cache = ctx.data().audio_cache();
+
+    if cache.is_none() {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Audio cache disabled")
+                        .description("Set `CACHE_DIR` to enable caching of audio files.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+    }
+
+    Ok(cache)
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Automatically move your Spotify playback to Spoticord when using /join
+#[poise::command(slash_command)]
+pub async fn autotransfer(
+    ctx: Context<'_>,
+
+    #[description = "Whether your playback should be transferred automatically"] enabled: bool,
+) -> Result<()> {
+    let db = ctx.data().database();
+
+    let user = match db.get_or_create_user(ctx.author().id.to_string()).await {
+        Ok(user) => user,
+        Err(why) => {
+            error!("Error fetching user: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(
+                                "Something went wrong whilst trying to update your preferences.",
+                            )
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    if let Err(why) = db.update_auto_transfer(user.id, enabled).await {
+        error!("Error updating user auto transfer preference: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description(
+                            "Something went wrong whilst trying to update your preferences.",
+                        )
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let description = if enabled {
+        "From now on, your Spotify playback will automatically be moved to Spoticord when you use `/join`."
+    } else {
+        "From now on, you will have to select the Spoticord device in Spotify yourself after using `/join`."
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .color(Colors::Success),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
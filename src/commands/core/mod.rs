@@ -1,3 +1,7 @@
+mod autotransfer;
+
+pub use autotransfer::*;
+
 use crate::Context;
 
 /// `/help` command
@@ -1,4 +1,6 @@
+pub mod admin;
 pub mod core;
 pub mod music; 
+pub mod settings;
 #[cfg(debug_assertions)]
 pub mod debug;
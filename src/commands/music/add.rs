@@ -0,0 +1,218 @@
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::{AutocompleteChoice, CreateEmbed};
+use spoticord_database::{error::DatabaseResultExt, QueuePolicy};
+use spoticord_session::{manager::SessionQuery, spotify};
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Add a song to the queue of the host
+#[poise::command(slash_command, guild_only)]
+pub async fn add(
+    ctx: Context<'_>,
+
+    #[description = "The song to add, or a Spotify link to it"]
+    #[autocomplete = "autocomplete_track"]
+    query: String,
+) -> Result<()> {
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot add to queue")
+                        .description("I'm currently not connected to any voice channel.\nUse `/join` to summon me to your voice channel.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if !session.active().await? {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot add to queue")
+                        .description("There is currently no host in this server.\nUse `/join` to become the host.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let owner = session.owner().await?;
+
+    if owner != ctx.author().id && !may_queue(&ctx).await? {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot add to queue")
+                        .description(
+                            "You are not allowed to add songs to the queue in this server.",
+                        )
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.defer().await?;
+
+    let database = ctx.data().database();
+
+    // Autocompleted values are track links, anything else is searched for
+    let track = match spoticord_player::link::parse_track(&query) {
+        Some(id) => id.to_base62().ok(),
+
+        // Links to anything other than a track cannot be queued
+        None if spoticord_player::link::parse(&query).is_some() => None,
+        None => match spotify::search_tracks(&database, owner.to_string(), &query).await {
+            Ok(results) => results.into_iter().next().map(|track| track.id),
+            Err(why) => {
+                error!("Failed to search for tracks: {why}");
+
+                None
+            }
+        },
+    };
+
+    let Some(track) = track else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot add to queue")
+                        .description("I couldn't find a song matching your query.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if let Err(why) = session.add_to_queue(&track, ctx.author().id).await {
+        error!("Failed to add {track} to the queue: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot add to queue")
+                        .description("Spotify was unable to add the song to the queue. Make sure that Spoticord is the active device of the host.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("Added to queue")
+                .description(format!(
+                    "<@{}> added <https://open.spotify.com/track/{track}> to the queue",
+                    ctx.author().id
+                ))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Check whether the queue policy of the server allows the author to add songs
+async fn may_queue(ctx: &Context<'_>) -> Result<bool> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    // Servers only get a row once their settings are changed, until then the defaults apply
+    let settings = ctx
+        .data()
+        .database()
+        .get_guild(guild.to_string())
+        .await
+        .optional()?;
+
+    let policy = settings
+        .as_ref()
+        .map_or(QueuePolicy::default(), |settings| settings.queue_policy());
+
+    let allowed = match policy {
+        QueuePolicy::Anyone => true,
+        QueuePolicy::HostOnly => false,
+        QueuePolicy::DjRole => {
+            let Some(dj_role) = settings.and_then(|settings| settings.dj_role) else {
+                return Ok(false);
+            };
+
+            ctx.author_member()
+                .await
+                .map(|member| member.roles.iter().any(|role| role.to_string() == dj_role))
+                .unwrap_or(false)
+        }
+    };
+
+    Ok(allowed)
+}
+
+async fn autocomplete_track(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let Some(guild) = ctx.guild_id() else {
+        return vec![];
+    };
+
+    if partial.trim().is_empty() {
+        return vec![];
+    }
+
+    let Some(session) = ctx.data().get_session(SessionQuery::Guild(guild)) else {
+        return vec![];
+    };
+
+    let Ok(owner) = session.owner().await else {
+        return vec![];
+    };
+
+    let results =
+        match spotify::search_tracks(&ctx.data().database(), owner.to_string(), partial).await {
+            Ok(results) => results,
+            Err(why) => {
+                error!("Failed to search for tracks: {why}");
+
+                return vec![];
+            }
+        };
+
+    results
+        .into_iter()
+        .map(|track| {
+            // Discord does not allow choice names longer than 100 characters
+            let name = format!("{} - {}", track.name, track.artists)
+                .chars()
+                .take(100)
+                .collect::<String>();
+
+            AutocompleteChoice::new(name, format!("spotify:track:{}", track.id))
+        })
+        .collect()
+}
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use log::error;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::CreateEmbed;
+use spoticord_database::{error::DatabaseResultExt, EqualizerPreset};
+use spoticord_player::equalizer::{self, BAND_FREQUENCIES};
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+#[derive(Debug, Clone, Copy, ChoiceParameter)]
+pub enum Preset {
+    #[name = "Flat"]
+    Flat,
+
+    #[name = "Bass boost"]
+    BassBoost,
+
+    #[name = "Vocal"]
+    Vocal,
+
+    #[name = "Treble"]
+    Treble,
+
+    #[name = "Loudness"]
+    Loudness,
+}
+
+impl From<Preset> for EqualizerPreset {
+    fn from(value: Preset) -> Self {
+        match value {
+            Preset::Flat => Self::Flat,
+            Preset::BassBoost => Self::BassBoost,
+            Preset::Vocal => Self::Vocal,
+            Preset::Treble => Self::Treble,
+            Preset::Loudness => Self::Loudness,
+        }
+    }
+}
+
+/// Show or change how the music sounds
+#[poise::command(slash_command, guild_only)]
+pub async fn eq(
+    ctx: Context<'_>,
+
+    #[description = "A built-in sound to start from"] preset: Option<Preset>,
+
+    #[description = "Gain at 60 Hz, from -12 to 12 dB"]
+    #[min = -12]
+    #[max = 12]
+    low: Option<f64>,
+
+    #[description = "Gain at 250 Hz, from -12 to 12 dB"]
+    #[min = -12]
+    #[max = 12]
+    low_mid: Option<f64>,
+
+    #[description = "Gain at 1 kHz, from -12 to 12 dB"]
+    #[min = -12]
+    #[max = 12]
+    mid: Option<f64>,
+
+    #[description = "Gain at 4 kHz, from -12 to 12 dB"]
+    #[min = -12]
+    #[max = 12]
+    high_mid: Option<f64>,
+
+    #[description = "Gain at 12 kHz, from -12 to 12 dB"]
+    #[min = -12]
+    #[max = 12]
+    high: Option<f64>,
+) -> Result<()> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+    let db = ctx.data().database();
+
+    // Servers only get a row once their settings are changed, until then the defaults apply
+    let settings = match db.get_guild(guild.to_string()).await.optional() {
+        Ok(settings) => settings,
+        Err(why) => {
+            error!("Error fetching guild settings: {why}");
+
+            return respond_error(&ctx).await;
+        }
+    };
+
+    let (current_preset, custom_gains) = settings.map_or_else(
+        || (EqualizerPreset::default(), vec![]),
+        |settings| (settings.equalizer_preset(), settings.equalizer_gains()),
+    );
+    let current = spoticord_session::equalizer(current_preset, &custom_gains);
+    let bands = [low, low_mid, mid, high_mid, high];
+
+    let (stored, preset, gains) = if bands.iter().any(Option::is_some) {
+        // Bands are adjusted on top of the chosen preset, or the current sound if no preset was chosen
+        let base = preset.map_or(current, |preset| {
+            spoticord_session::equalizer(preset.into(), &[])
+        });
+
+        let mut gains = base.gains();
+        for (gain, band) in gains.iter_mut().zip(bands) {
+            if let Some(band) = band {
+                *gain = band as f32;
+            }
+        }
+
+        let preset = equalizer::Preset::Custom(gains);
+
+        (EqualizerPreset::Custom, preset, preset.gains().to_vec())
+    } else if let Some(preset) = preset {
+        // Custom gains are kept around, so that they can be restored later on
+        let stored = EqualizerPreset::from(preset);
+
+        (
+            stored,
+            spoticord_session::equalizer(stored, &[]),
+            custom_gains,
+        )
+    } else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Equalizer")
+                        .description(describe(current))
+                        .color(Colors::Info),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    // The equalizer is stored for the whole server, so changing it takes the same permission as /settings
+    if !can_manage_guild(&ctx).await {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change equalizer")
+                        .description(
+                            "You need the **Manage Server** permission to change the equalizer of this server.",
+                        )
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    if let Err(why) = db.get_or_create_guild(guild.to_string()).await {
+        error!("Error fetching guild settings: {why}");
+
+        return respond_error(&ctx).await;
+    }
+
+    if let Err(why) = db.update_equalizer(guild.to_string(), stored, &gains).await {
+        error!("Error updating equalizer: {why}");
+
+        return respond_error(&ctx).await;
+    }
+
+    if let Some(session) = ctx.data().get_session(SessionQuery::Guild(guild)) {
+        session.set_equalizer(preset).await;
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("Equalizer changed")
+                .description(describe(preset))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// The name of a preset, along with the gain of every band
+fn describe(preset: equalizer::Preset) -> String {
+    let bands = BAND_FREQUENCIES
+        .iter()
+        .zip(preset.gains())
+        .map(|(frequency, gain)| {
+            let frequency = if *frequency >= 1000.0 {
+                format!("{} kHz", frequency / 1000.0)
+            } else {
+                format!("{frequency} Hz")
+            };
+
+            format!("`{frequency:>6}` {gain:+.1} dB")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("**{}**\n{bands}", preset.name())
+}
+
+async fn can_manage_guild(ctx: &Context<'_>) -> bool {
+    ctx.author_member()
+        .await
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_guild())
+}
+
+async fn respond_error(ctx: &Context<'_>) -> Result<()> {
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description("Something went wrong whilst trying to update the equalizer.")
+                    .color(Colors::Error),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -175,7 +175,7 @@ pub async fn join(ctx: Context<'_>) -> Result<()> {
         }
     }
 
-    if let Some(session) = session_opt {
+    let session = if let Some(session) = session_opt {
         if let Err(why) = session.reactivate(ctx.author().id).await {
             error!("Failed to reactivate session: {why}");
 
@@ -195,37 +195,71 @@ pub async fn join(ctx: Context<'_>) -> Result<()> {
 
             return Ok(());
         }
-    } else if let Err(why) = manager
-        .create_session(
-            ctx.serenity_context(),
-            guild.id,
-            channel,
-            ctx.channel_id(),
-            ctx.author().id,
-        )
-        .await
-    {
-        error!("Failed to create session: {why}");
 
-        let description = if matches!(why, spoticord_session::error::Error::AuthenticationFailed) {
-            "Unable to authenticate with Spotify. Did you change your password?\n\nThe broken credentials used have been deleted.\n\nYou might need to relink your account using `/link`."
-        } else {
-            "An error occured whilst trying to create a session. Please try again."
-        };
-
-        ctx.send(
-            CreateReply::default()
-                .embed(
-                    CreateEmbed::new()
-                        .title("Failed to create session")
-                        .description(description)
-                        .color(Colors::Error),
+        session
+    } else {
+        match manager
+            .create_session(
+                ctx.serenity_context(),
+                guild.id,
+                channel,
+                ctx.channel_id(),
+                ctx.author().id,
+            )
+            .await
+        {
+            Ok(session) => session,
+            Err(why) => {
+                error!("Failed to create session: {why}");
+
+                let description = if matches!(
+                    why,
+                    spoticord_session::error::Error::AuthenticationFailed
+                ) {
+                    "Unable to authenticate with Spotify. Did you change your password?\n\nThe broken credentials used have been deleted.\n\nYou might need to relink your account using `/link`."
+                } else {
+                    "An error occured whilst trying to create a session. Please try again."
+                };
+
+                ctx.send(
+                    CreateReply::default()
+                        .embed(
+                            CreateEmbed::new()
+                                .title("Failed to create session")
+                                .description(description)
+                                .color(Colors::Error),
+                        )
+                        .ephemeral(true),
                 )
-                .ephemeral(true),
-        )
-        .await?;
+                .await?;
 
-        return Ok(());
+                return Ok(());
+            }
+        }
+    };
+
+    let auto_transfer = manager
+        .database()
+        .get_user(ctx.author().id.to_string())
+        .await
+        .map(|user| user.auto_transfer)
+        .unwrap_or(false);
+
+    let mut description = format!("Come listen along in <#{}>", channel);
+    let mut footer = "Select your device in Spotify, or use /play to start playing";
+
+    if auto_transfer {
+        match session.transfer_playback().await {
+            Ok(true) => footer = "Your Spotify playback has been transferred to Spoticord",
+            Ok(false) => {
+                description += "\n\nYou are currently not playing anything on Spotify, so there was nothing to transfer.";
+            }
+            Err(why) => {
+                error!("Failed to transfer playback: {why}");
+
+                description += "\n\nSomething went wrong whilst trying to transfer your playback.";
+            }
+        }
     }
 
     ctx.send(
@@ -235,10 +269,8 @@ pub async fn join(ctx: Context<'_>) -> Result<()> {
                     CreateEmbedAuthor::new("Connected to voice channel")
                         .icon_url("https://spoticord.com/speaker.png"),
                 )
-                .description(format!("Come listen along in <#{}>", channel))
-                .footer(CreateEmbedFooter::new(
-                    "You must manually select your device in Spotify",
-                ))
+                .description(description)
+                .footer(CreateEmbedFooter::new(footer))
                 .color(Colors::Info),
         ),
     )
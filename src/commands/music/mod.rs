@@ -1,31 +1,26 @@
-use crate::Context;
+mod add;
+mod eq;
+mod join;
+mod play;
+mod queue;
+mod repeat;
+mod seek;
+mod shuffle;
+mod speed;
+mod volume;
 
-/// `/join` command
-pub fn join() -> poise::Command<crate::Data, anyhow::Error> {
-    poise::Command {
-        name: "join",
-        description: "Make the bot join your current voice channel",
-        category: None,
-        action: |ctx| {
-            Box::pin(async move {
-                let guild_id = ctx.guild_id().ok_or("You must be in a server")?;
-                let channel_id = ctx
-                    .author_voice_channel()
-                    .ok_or("You must be in a voice channel")?;
-
-                let manager = songbird::get(ctx.serenity_context())
-                    .await
-                    .ok_or("Songbird Voice client not initialised")?
-                    .clone();
+pub use add::*;
+pub use eq::*;
+pub use join::*;
+pub use play::*;
+pub use queue::*;
+pub use repeat::*;
+pub use seek::*;
+pub use shuffle::*;
+pub use speed::*;
+pub use volume::*;
 
-                manager.join(guild_id, channel_id).await?;
-                ctx.say("Joined your voice channel.").await?;
-                Ok(())
-            })
-        },
-        ..Default::default()
-    }
-}
+use crate::Context;
 
 /// `/disconnect` command
 pub fn disconnect() -> poise::Command<crate::Data, anyhow::Error> {
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Play a Spotify track, album, playlist, artist, episode or show
+#[poise::command(slash_command, guild_only)]
+pub async fn play(
+    ctx: Context<'_>,
+
+    #[description = "A Spotify link or URI, like https://open.spotify.com/track/..."] link: String,
+) -> Result<()> {
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(id) = spoticord_player::link::parse(&link) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Invalid Spotify link")
+                        .description(
+                            "You can only play links to a Spotify track, album, playlist, artist, episode or show.\n\
+                            These links look like `https://open.spotify.com/track/...` or `spotify:track:...`.",
+                        )
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot play music")
+                        .description("I'm currently not connected to any voice channel.\nUse `/join` to summon me to your voice channel.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if !session.active().await? {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot play music")
+                        .description("There is currently no host in this server.\nUse `/join` to become the host.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    if session.owner().await? != ctx.author().id {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot play music")
+                        .description("Only the host may start playing something new.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.defer().await?;
+
+    if let Err(why) = session.player().await?.load(id).await {
+        error!("Failed to load {link}: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot play music")
+                        .description("Spotify was unable to play the provided link. It might be unavailable in your region, or it might not exist.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("Now playing")
+                .description(format!("Started playing <{link}>"))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::CreateEmbed;
+use spoticord_player::info::RepeatMode;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+#[derive(Debug, ChoiceParameter)]
+pub enum Repeat {
+    #[name = "Do not repeat"]
+    Off,
+
+    #[name = "Repeat the current playlist or album"]
+    Context,
+
+    #[name = "Repeat the current song"]
+    Track,
+}
+
+impl From<Repeat> for RepeatMode {
+    fn from(value: Repeat) -> Self {
+        match value {
+            Repeat::Off => Self::Off,
+            Repeat::Context => Self::Context,
+            Repeat::Track => Self::Track,
+        }
+    }
+}
+
+/// Change what should be repeated
+#[poise::command(slash_command, guild_only)]
+pub async fn repeat(
+    ctx: Context<'_>,
+
+    #[description = "What should be repeated"] mode: Repeat,
+) -> Result<()> {
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change repeat")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let player = session.player().await?;
+
+    let Ok(Some(_)) = player.playback_info().await else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change repeat")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let mode = RepeatMode::from(mode);
+    if let Err(why) = player.set_repeat(mode).await {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change repeat")
+                        .description(why.to_string())
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let description = match mode {
+        RepeatMode::Off => "Repeat has been turned off.",
+        RepeatMode::Context => "The current playlist or album will now be repeated.",
+        RepeatMode::Track => "The current song will now be repeated.",
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("Repeat changed")
+                .description(description)
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
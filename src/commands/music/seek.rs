@@ -0,0 +1,238 @@
+use anyhow::Result;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+#[derive(Debug, PartialEq, Eq)]
+enum SeekTarget {
+    Absolute(u32),
+    Forward(u32),
+    Backward(u32),
+}
+
+/// Jump to a position in the current song
+#[poise::command(slash_command, guild_only)]
+pub async fn seek(
+    ctx: Context<'_>,
+
+    #[description = "The position to jump to, like 1:30 or 90s. Use +15 or -15 to skip relative to now."]
+    position: String,
+) -> Result<()> {
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(target) = parse_position(&position) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Invalid position")
+                        .description(
+                            "The position must look like `1:30`, `90s`, `1m30s`, `+15` or `-15`.",
+                        )
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot seek")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let player = session.player().await?;
+
+    let Ok(Some(playback_info)) = player.playback_info().await else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot seek")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let current = playback_info.current_position();
+    let position = match target {
+        SeekTarget::Absolute(position) => position,
+        SeekTarget::Forward(offset) => current.saturating_add(offset),
+        SeekTarget::Backward(offset) => current.saturating_sub(offset),
+    };
+    let position = u32::min(position, playback_info.duration());
+
+    if let Err(why) = player.seek(position).await {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot seek")
+                        .description(why.to_string())
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("Jumped to new position")
+                .description(format!(
+                    "Playback has been moved to **{} / {}**",
+                    spoticord_utils::time_to_string(position / 1000),
+                    spoticord_utils::time_to_string(playback_info.duration() / 1000)
+                ))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Parse a user provided position, prefixed with `+` or `-` for relative seeking
+fn parse_position(input: &str) -> Option<SeekTarget> {
+    let input = input.trim();
+
+    if let Some(offset) = input.strip_prefix('+') {
+        parse_duration(offset).map(SeekTarget::Forward)
+    } else if let Some(offset) = input.strip_prefix('-') {
+        parse_duration(offset).map(SeekTarget::Backward)
+    } else {
+        parse_duration(input).map(SeekTarget::Absolute)
+    }
+}
+
+/// Parse durations like `90`, `1:30`, `1:02:03`, `90s` or `1h2m3s` into milliseconds
+fn parse_duration(input: &str) -> Option<u32> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return None;
+    }
+
+    let seconds = if input.contains(':') {
+        let parts = input.split(':').collect::<Vec<_>>();
+
+        if parts.len() > 3 {
+            return None;
+        }
+
+        parts.into_iter().try_fold(0u32, |acc, part| {
+            acc.checked_mul(60)?.checked_add(part.parse().ok()?)
+        })?
+    } else {
+        let mut total = 0u32;
+        let mut number = String::new();
+
+        for c in input.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+
+            let multiplier = match c {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+
+            let value = number.parse::<u32>().ok()?;
+            total = total.checked_add(value.checked_mul(multiplier)?)?;
+            number.clear();
+        }
+
+        if !number.is_empty() {
+            total = total.checked_add(number.parse().ok()?)?;
+        }
+
+        total
+    };
+
+    seconds.checked_mul(1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_seconds() {
+        assert_eq!(parse_duration("90"), Some(90_000));
+        assert_eq!(parse_duration(" 0 "), Some(0));
+    }
+
+    #[test]
+    fn parses_clock_times() {
+        assert_eq!(parse_duration("1:23"), Some(83_000));
+        assert_eq!(parse_duration("1:02:03"), Some(3_723_000));
+        assert_eq!(parse_duration("0:90"), Some(90_000));
+    }
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse_duration("90s"), Some(90_000));
+        assert_eq!(parse_duration("1m30s"), Some(90_000));
+        assert_eq!(parse_duration("1h2m3s"), Some(3_723_000));
+        assert_eq!(parse_duration("2m5"), Some(125_000));
+    }
+
+    #[test]
+    fn parses_relative_positions() {
+        assert_eq!(parse_position("+10"), Some(SeekTarget::Forward(10_000)));
+        assert_eq!(parse_position("-5"), Some(SeekTarget::Backward(5_000)));
+        assert_eq!(parse_position("+1:30"), Some(SeekTarget::Forward(90_000)));
+        assert_eq!(parse_position("1:30"), Some(SeekTarget::Absolute(90_000)));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse_position(""), None);
+        assert_eq!(parse_position("+"), None);
+        assert_eq!(parse_position("--5"), None);
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration("1:"), None);
+        assert_eq!(parse_duration(":30"), None);
+        assert_eq!(parse_duration("1:2:3:4"), None);
+        assert_eq!(parse_duration("1.5"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("m"), None);
+    }
+
+    #[test]
+    fn rejects_overflowing_input() {
+        // The position is kept in milliseconds, which only fit about 49 days
+        assert_eq!(parse_duration("4294968"), None);
+        assert_eq!(parse_duration("99999999999"), None);
+        assert_eq!(parse_duration("1193047h"), None);
+        assert_eq!(parse_duration("99999:99999:99999"), None);
+    }
+}
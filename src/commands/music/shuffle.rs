@@ -0,0 +1,89 @@
+use anyhow::Result;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Turn shuffle on or off, or toggle it when no option is given
+#[poise::command(slash_command, guild_only)]
+pub async fn shuffle(
+    ctx: Context<'_>,
+
+    #[description = "Whether shuffle should be turned on"] enabled: Option<bool>,
+) -> Result<()> {
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change shuffle")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let player = session.player().await?;
+
+    let Ok(Some(playback_info)) = player.playback_info().await else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change shuffle")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let enabled = enabled.unwrap_or(!playback_info.shuffle());
+    if let Err(why) = player.set_shuffle(enabled).await {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change shuffle")
+                        .description(why.to_string())
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(if enabled {
+                    "Shuffle enabled"
+                } else {
+                    "Shuffle disabled"
+                })
+                .description(if enabled {
+                    "Upcoming songs will now be played in a random order."
+                } else {
+                    "Upcoming songs will now be played in order."
+                })
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
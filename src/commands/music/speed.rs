@@ -0,0 +1,102 @@
+use anyhow::Result;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Show or change how fast audio is played
+#[poise::command(slash_command, guild_only)]
+pub async fn speed(
+    ctx: Context<'_>,
+
+    #[description = "The new speed, from 0.5x to 2x. Music changes in pitch, episodes don't"]
+    #[min = 0.5]
+    #[max = 2]
+    factor: Option<f64>,
+) -> Result<()> {
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change speed")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if !session.active().await? {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change speed")
+                        .description("I'm currently not playing any music in this server.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let Some(factor) = factor else {
+        let speed = session.speed().await?;
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Current speed")
+                        .description(format!("Audio is currently played at **{speed}x** speed"))
+                        .color(Colors::Info),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    // Discord allows any number of decimals, which isn't very useful for a speed
+    let factor = ((factor * 100.0).round() / 100.0) as f32;
+
+    if let Err(why) = session.set_speed(factor).await {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Cannot change speed")
+                        .description(why.to_string())
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("Speed changed")
+                .description(format!("Audio is now played at **{factor}x** speed"))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
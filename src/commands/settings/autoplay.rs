@@ -0,0 +1,60 @@
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Keep playing related songs once an album or playlist has ended
+#[poise::command(slash_command, guild_only)]
+pub async fn autoplay(
+    ctx: Context<'_>,
+
+    #[description = "Whether related songs should keep playing once an album or playlist has ended"]
+    enabled: bool,
+) -> Result<()> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+    let db = ctx.data().database();
+
+    let result = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => db.update_autoplay(settings.id, enabled).await,
+        Err(why) => Err(why),
+    };
+
+    if let Err(why) = result {
+        error!("Error updating autoplay: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description("Something went wrong whilst trying to update the settings.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let description = if enabled {
+        "Related songs will now be played once the music runs out, starting from the next `/join`."
+    } else {
+        "Autoplay has been turned off, starting from the next `/join`."
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .color(Colors::Success),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use log::error;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::CreateEmbed;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+#[derive(Debug, ChoiceParameter)]
+pub enum Bitrate {
+    #[name = "Low (96kbps)"]
+    Low,
+
+    #[name = "Normal (160kbps)"]
+    Normal,
+
+    #[name = "High (320kbps)"]
+    High,
+}
+
+impl Bitrate {
+    fn kbps(&self) -> u16 {
+        match self {
+            Self::Low => 96,
+            Self::Normal => 160,
+            Self::High => 320,
+        }
+    }
+}
+
+/// Change the quality at which music is streamed
+#[poise::command(slash_command, guild_only)]
+pub async fn bitrate(
+    ctx: Context<'_>,
+
+    #[description = "The streaming quality"] quality: Bitrate,
+) -> Result<()> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+    let db = ctx.data().database();
+    let kbps = quality.kbps();
+
+    let result = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => db.update_bitrate(settings.id, kbps as i16).await,
+        Err(why) => Err(why),
+    };
+
+    if let Err(why) = result {
+        error!("Error updating bitrate: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description("Something went wrong whilst trying to update the settings.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let max_bitrate = spoticord_config::max_bitrate();
+    let mut description =
+        format!("Music will now be streamed at **{kbps}kbps**, starting from the next `/join`.");
+
+    if kbps > max_bitrate {
+        description += &format!(
+            "\n\nThis bot is limited to {max_bitrate}kbps, so a lower bitrate will be used instead."
+        );
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .color(Colors::Success),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Let tracks overlap when one ends and the next one starts
+#[poise::command(slash_command, guild_only)]
+pub async fn crossfade(
+    ctx: Context<'_>,
+
+    #[description = "How many seconds the tracks should overlap, 0 turns crossfading off"]
+    #[min = 0]
+    #[max = 12]
+    seconds: u8,
+) -> Result<()> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+    let db = ctx.data().database();
+
+    let result = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => db.update_crossfade(settings.id, seconds as i16).await,
+        Err(why) => Err(why),
+    };
+
+    if let Err(why) = result {
+        error!("Error updating crossfade: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description("Something went wrong whilst trying to update the settings.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let description = if seconds == 0 {
+        "Crossfading has been turned off, starting from the next `/join`.".to_string()
+    } else {
+        format!(
+            "Tracks will now overlap for **{seconds} seconds**, starting from the next `/join`."
+        )
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .color(Colors::Success),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+mod autoplay;
+mod bitrate;
+mod crossfade;
+mod normalisation;
+mod queue;
+mod view;
+
+use anyhow::Result;
+
+use crate::bot::Context;
+
+/// Change how Spoticord behaves in this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "view::view",
+        "queue::queue",
+        "bitrate::bitrate",
+        "normalisation::normalisation",
+        "crossfade::crossfade",
+        "autoplay::autoplay"
+    ),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn settings(_ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use log::error;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::CreateEmbed;
+use spoticord_database::NormalisationMode;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+#[derive(Debug, ChoiceParameter)]
+pub enum Mode {
+    #[name = "Make every track equally loud"]
+    Track,
+
+    #[name = "Keep loudness differences within an album"]
+    Album,
+}
+
+impl From<Mode> for NormalisationMode {
+    fn from(value: Mode) -> Self {
+        match value {
+            Mode::Track => Self::Track,
+            Mode::Album => Self::Album,
+        }
+    }
+}
+
+/// Even out the loudness of different tracks
+#[poise::command(slash_command, guild_only)]
+pub async fn normalisation(
+    ctx: Context<'_>,
+
+    #[description = "Whether loudness normalisation should be used"] enabled: bool,
+    #[description = "How the loudness should be evened out"] mode: Option<Mode>,
+
+    #[description = "Extra gain for every track, from -10 to 10 dB"]
+    #[min = -10]
+    #[max = 10]
+    pregain: Option<f64>,
+
+    #[description = "The level above which the limiter kicks in, from -10 to 0 dBFS"]
+    #[min = -10]
+    #[max = 0]
+    threshold: Option<f64>,
+) -> Result<()> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+    let db = ctx.data().database();
+
+    let result = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => {
+            // Options that were left out keep their current value
+            let mode = mode.map_or(settings.normalisation_mode(), NormalisationMode::from);
+            let pregain = pregain.unwrap_or(settings.normalisation_pregain);
+            let threshold = threshold.unwrap_or(settings.normalisation_threshold);
+
+            db.update_normalisation(&settings.id, enabled, mode, pregain, threshold)
+                .await
+        }
+        Err(why) => Err(why),
+    };
+
+    if let Err(why) = result {
+        error!("Error updating normalisation: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description("Something went wrong whilst trying to update the settings.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let description = if enabled {
+        "Loudness normalisation has been turned on, starting from the next `/join`."
+    } else {
+        "Loudness normalisation has been turned off, starting from the next `/join`."
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .color(Colors::Success),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
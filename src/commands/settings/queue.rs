@@ -0,0 +1,118 @@
+use anyhow::Result;
+use log::error;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::{CreateEmbed, Role};
+use spoticord_database::QueuePolicy;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+#[derive(Debug, ChoiceParameter)]
+pub enum Policy {
+    #[name = "Anyone may add songs"]
+    Anyone,
+
+    #[name = "Only members with the DJ role may add songs"]
+    DjRole,
+
+    #[name = "Only the host may add songs"]
+    HostOnly,
+}
+
+impl From<Policy> for QueuePolicy {
+    fn from(value: Policy) -> Self {
+        match value {
+            Policy::Anyone => Self::Anyone,
+            Policy::DjRole => Self::DjRole,
+            Policy::HostOnly => Self::HostOnly,
+        }
+    }
+}
+
+/// Change who may add songs to the queue using /add
+#[poise::command(slash_command, guild_only)]
+pub async fn queue(
+    ctx: Context<'_>,
+
+    #[description = "Who may add songs to the queue"] policy: Policy,
+    #[description = "The DJ role, required when only DJs may add songs"] role: Option<Role>,
+) -> Result<()> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+    let policy = QueuePolicy::from(policy);
+
+    if policy == QueuePolicy::DjRole && role.is_none() {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Missing DJ role")
+                        .description("You must provide a role when only DJs may add songs.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let db = ctx.data().database();
+
+    // Only remember the role if it is actually being used
+    let role = role.filter(|_| policy == QueuePolicy::DjRole);
+
+    let result = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => {
+            db.update_queue_policy(
+                settings.id,
+                policy,
+                role.as_ref().map(|role| role.id.to_string()),
+            )
+            .await
+        }
+        Err(why) => Err(why),
+    };
+
+    if let Err(why) = result {
+        error!("Error updating queue policy: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description("Something went wrong whilst trying to update the settings.")
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let description = match (policy, role) {
+        (QueuePolicy::DjRole, Some(role)) => {
+            format!(
+                "From now on, only the host and members with <@&{}> may add songs using `/add`.",
+                role.id
+            )
+        }
+        (QueuePolicy::HostOnly, _) => {
+            "From now on, only the host may add songs using `/add`.".into()
+        }
+        _ => "From now on, anyone may add songs using `/add`.".into(),
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description(description)
+                    .color(Colors::Success),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_database::{NormalisationMode, QueuePolicy};
+use spoticord_utils::discord::Colors;
+
+use crate::bot::Context;
+
+/// Show the current settings of this server
+#[poise::command(slash_command, guild_only)]
+pub async fn view(ctx: Context<'_>) -> Result<()> {
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let settings = match ctx
+        .data()
+        .database()
+        .get_guild_or_default(guild.to_string())
+        .await
+    {
+        Ok(settings) => settings,
+        Err(why) => {
+            error!("Error fetching guild settings: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(
+                                "Something went wrong whilst trying to fetch the settings.",
+                            )
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    let queue_policy = match (settings.queue_policy(), &settings.dj_role) {
+        (QueuePolicy::Anyone, _) => "Anyone".to_string(),
+        (QueuePolicy::DjRole, Some(role)) => format!("Host and <@&{role}>"),
+        (QueuePolicy::DjRole, None) | (QueuePolicy::HostOnly, _) => "Host only".to_string(),
+    };
+
+    let max_bitrate = spoticord_config::max_bitrate();
+    let bitrate = if settings.bitrate as u16 > max_bitrate {
+        format!("{}kbps (limited to {max_bitrate}kbps)", settings.bitrate)
+    } else {
+        format!("{}kbps", settings.bitrate)
+    };
+
+    let normalisation = if settings.normalisation {
+        let mode = match settings.normalisation_mode() {
+            NormalisationMode::Track => "per track",
+            NormalisationMode::Album => "per album",
+        };
+
+        format!(
+            "On, {mode}\nPregain: {:+.1}dB\nLimiter threshold: {:.1}dBFS",
+            settings.normalisation_pregain, settings.normalisation_threshold
+        )
+    } else {
+        "Off".to_string()
+    };
+
+    let crossfade = match settings.crossfade {
+        0 => "Off".to_string(),
+        seconds => format!("{seconds} seconds"),
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title("Server settings")
+                    .field("Who may use /add", queue_policy, false)
+                    .field("Bitrate", bitrate, true)
+                    .field("Loudness normalisation", normalisation, true)
+                    .field("Crossfade", crossfade, true)
+                    .field(
+                        "Autoplay",
+                        if settings.autoplay { "On" } else { "Off" },
+                        true,
+                    )
+                    .field(
+                        "Equalizer",
+                        spoticord_session::equalizer(
+                            settings.equalizer_preset(),
+                            &settings.equalizer_gains(),
+                        )
+                        .name(),
+                        true,
+                    )
+                    .color(Colors::Info),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}